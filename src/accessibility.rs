@@ -0,0 +1,218 @@
+use avian2d::prelude::LinearVelocity;
+use bevy::audio::{Pitch, PitchBundle};
+use bevy::color::palettes::css::RED;
+use bevy::prelude::*;
+use bevy_persistent::Persistent;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::landing_rules::LandingRules;
+use crate::radar::RadarAltimeter;
+use crate::settings::Settings;
+use crate::spaceship::Player;
+use crate::state::GameState;
+
+/// Text scales offered by the settings menu.
+pub const TEXT_SCALES: [f32; 3] = [1.0, 1.25, 1.5];
+/// Tone played when the vertical velocity enters the landing window, above the altitude callouts.
+const SAFE_BAND_CUE_FREQUENCY: f32 = 1320.0;
+
+pub struct AccessibilityPlugin;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                scale_ui_texts_system,
+                play_safe_band_cue_system.run_if(in_state(GameState::Landing)),
+            ),
+        );
+    }
+}
+
+// Conditions
+pub fn background_rotation_enabled(settings: Res<Persistent<Settings>>) -> bool {
+    settings.accessibility.background_rotation
+}
+
+// Systems
+/// Scales every UI text from the font sizes it was spawned with, so scaling twice never compounds.
+fn scale_ui_texts_system(
+    mut commands: Commands,
+    settings: Res<Persistent<Settings>>,
+    mut texts_query: Query<(Entity, &mut Text, Option<&BaseFontSizes>), With<Node>>,
+) {
+    let text_scale = settings.accessibility.text_scale;
+    for (entity, mut text, base_font_sizes) in texts_query.iter_mut() {
+        let base_font_sizes = match base_font_sizes {
+            Some(base_font_sizes) if settings.is_changed() => base_font_sizes.0.clone(),
+            Some(_) => continue,
+            None => {
+                let base_font_sizes: Vec<f32> = text
+                    .sections
+                    .iter()
+                    .map(|section| section.style.font_size)
+                    .collect();
+                commands
+                    .entity(entity)
+                    .insert(BaseFontSizes(base_font_sizes.clone()));
+                if text_scale == 1.0 {
+                    continue;
+                }
+                base_font_sizes
+            }
+        };
+        for (section, base_font_size) in text.sections.iter_mut().zip(base_font_sizes) {
+            section.style.font_size = base_font_size * text_scale;
+        }
+    }
+}
+
+/// Announces with a chime when the lander, close to the ground, slows down into the landing window.
+fn play_safe_band_cue_system(
+    mut commands: Commands,
+    mut pitch_assets: ResMut<Assets<Pitch>>,
+    settings: Res<Persistent<Settings>>,
+    landing_rules: Res<LandingRules>,
+    player_query: Query<(&LinearVelocity, &RadarAltimeter), With<Player>>,
+    mut was_in_band: Local<bool>,
+) {
+    let Ok((linear_velocity, radar_altimeter)) = player_query.get_single() else {
+        return;
+    };
+    let in_band = radar_altimeter
+        .height()
+        .is_some_and(|height| height < landing_rules.warning_height)
+        && landing_rules.is_safe(linear_velocity.y, radar_altimeter.platform_below());
+    if in_band && !*was_in_band && settings.accessibility.safe_band_cue {
+        commands.spawn(PitchBundle {
+            source: pitch_assets.add(Pitch::new(
+                SAFE_BAND_CUE_FREQUENCY,
+                Duration::from_millis(250),
+            )),
+            settings: PlaybackSettings::DESPAWN.with_volume(settings.sfx_volume()),
+        });
+    }
+    *was_in_band = in_band;
+}
+
+// Resources
+/// Persisted as part of the settings.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    pub palette: Palette,
+    /// Brighter HUD labels, a thicker speed indicator and strong menu selection borders
+    pub high_contrast: bool,
+    pub text_scale: f32,
+    /// Menu titles and the speedometer warning blink, otherwise they stay steady
+    pub blinking: bool,
+    pub background_rotation: bool,
+    /// The game camera zooms into the platform below when close enough
+    pub camera_snaps: bool,
+    pub safe_band_cue: bool,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self {
+            palette: Palette::Standard,
+            high_contrast: false,
+            text_scale: 1.0,
+            blinking: true,
+            background_rotation: true,
+            camera_snaps: true,
+            safe_band_cue: false,
+        }
+    }
+}
+
+impl AccessibilitySettings {
+    pub fn hud_label_color(&self) -> Color {
+        if self.high_contrast {
+            Color::WHITE
+        } else {
+            Color::srgb(0.6, 0.6, 0.6)
+        }
+    }
+
+    /// Next text scale, wrapping around to the smallest one.
+    pub fn next_text_scale(&self) -> f32 {
+        TEXT_SCALES
+            .iter()
+            .copied()
+            .find(|scale| *scale > self.text_scale + 0.01)
+            .unwrap_or(TEXT_SCALES[0])
+    }
+}
+
+/// Speedometer colors, the colorblind palettes keep the bar, the safe band and the warning apart
+/// for red-green (deuteranopia, protanopia) and blue-yellow (tritanopia) color blindness.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Palette {
+    #[default]
+    Standard,
+    RedGreen,
+    BlueYellow,
+}
+
+impl Palette {
+    pub fn next(&self) -> Self {
+        match self {
+            Palette::Standard => Palette::RedGreen,
+            Palette::RedGreen => Palette::BlueYellow,
+            Palette::BlueYellow => Palette::Standard,
+        }
+    }
+
+    /// Localization key of the palette name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Palette::Standard => "palette.standard",
+            Palette::RedGreen => "palette.red_green",
+            Palette::BlueYellow => "palette.blue_yellow",
+        }
+    }
+
+    pub fn speed_bar(&self) -> Color {
+        match self {
+            Palette::Standard => Color::srgb(0.32, 0.75, 0.03),
+            Palette::RedGreen => Color::srgb(0.0, 0.45, 0.7),
+            Palette::BlueYellow => Color::srgb(0.0, 0.62, 0.45),
+        }
+    }
+
+    pub fn safe_band(&self) -> Color {
+        match self {
+            Palette::Standard => Color::srgb(0.77, 0.84, 0.11),
+            Palette::RedGreen => Color::srgb(0.94, 0.89, 0.26),
+            Palette::BlueYellow => Color::srgb(0.84, 0.37, 0.0),
+        }
+    }
+
+    pub fn warning(&self) -> Color {
+        match self {
+            Palette::Standard => Color::from(RED),
+            Palette::RedGreen => Color::srgb(0.8, 0.47, 0.65),
+            Palette::BlueYellow => Color::WHITE,
+        }
+    }
+}
+
+// Components
+#[derive(Component, Debug)]
+struct BaseFontSizes(Vec<f32>);
+
+#[test]
+fn accessibility_settings_test() {
+    let mut accessibility = AccessibilitySettings::default();
+    assert_eq!(accessibility.next_text_scale(), 1.25);
+    accessibility.text_scale = 1.5;
+    assert_eq!(accessibility.next_text_scale(), 1.0);
+    assert_eq!(Palette::BlueYellow.next(), Palette::Standard);
+    for palette in [Palette::Standard, Palette::RedGreen, Palette::BlueYellow] {
+        assert_ne!(palette.speed_bar(), palette.safe_band());
+        assert_ne!(palette.safe_band(), palette.warning());
+    }
+}
@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::asset_loader::UiAssets;
+use crate::attract::attract_mode_active;
+use crate::localization::Localization;
+use crate::menu::{no_text_input, MenuAction};
+use crate::state::{AppState, GameState};
+
+/// Landing score multiplier while the trajectory prediction is shown.
+pub const TRAJECTORY_SCORE_MULTIPLIER: f32 = 0.5;
+
+pub struct AssistsPlugin;
+
+impl Plugin for AssistsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Assists>()
+            .add_systems(OnEnter(AppState::Game), spawn_assists_text_system)
+            .add_systems(OnEnter(GameState::Setup), clear_assists_used_system)
+            .add_systems(
+                Update,
+                (
+                    handle_assist_keys_system
+                        .run_if(not(attract_mode_active))
+                        .run_if(no_text_input),
+                    update_assists_text_system,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Game)),
+            )
+            .add_systems(
+                Update,
+                latch_assists_used_system
+                    .run_if(in_state(GameState::Landing))
+                    .run_if(trajectory_assist_enabled),
+            );
+    }
+}
+
+// Conditions
+pub fn trajectory_assist_enabled(assists: Res<Assists>) -> bool {
+    assists.trajectory
+}
+
+// Systems
+fn spawn_assists_text_system(mut commands: Commands, assets: Res<UiAssets>) {
+    commands.spawn((
+        StateScoped(AppState::Game),
+        AssistsText,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: assets.font_vt323.clone(),
+                color: Color::srgb(0.77, 0.84, 0.11),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(36.0),
+            left: Val::Px(20.0),
+            ..default()
+        }),
+    ));
+}
+
+fn handle_assist_keys_system(
+    menu_action_state: Res<ActionState<MenuAction>>,
+    mut assists: ResMut<Assists>,
+) {
+    if menu_action_state.just_pressed(&MenuAction::ToggleTrajectory) {
+        assists.trajectory = !assists.trajectory;
+        info!("Trajectory assist enabled: {:?}", assists.trajectory);
+    }
+}
+
+fn clear_assists_used_system(mut assists: ResMut<Assists>) {
+    assists.used = false;
+}
+
+/// Remembers the trajectory was shown this landing attempt, even if hidden before the touchdown.
+fn latch_assists_used_system(mut assists: ResMut<Assists>) {
+    if !assists.used {
+        assists.used = true;
+    }
+}
+
+fn update_assists_text_system(
+    assists: Res<Assists>,
+    localization: Res<Localization>,
+    mut text_query: Query<&mut Text, With<AssistsText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    if !assists.is_changed() && !localization.is_changed() && !text.is_added() {
+        return;
+    }
+    text.sections[0].value = if assists.trajectory {
+        localization.format(
+            "assists.trajectory",
+            &[&assists.score_multiplier().to_string()],
+        )
+    } else {
+        "".into()
+    };
+}
+
+// Resources
+#[derive(Resource, Debug, Default, Clone)]
+pub struct Assists {
+    pub trajectory: bool,
+    /// The trajectory was shown at some point of the current landing attempt
+    pub used: bool,
+}
+
+impl Assists {
+    /// Applied to every landing score, each assist used during the attempt makes landing easier
+    /// and cheaper.
+    pub fn score_multiplier(&self) -> f32 {
+        if self.trajectory || self.used {
+            TRAJECTORY_SCORE_MULTIPLIER
+        } else {
+            1.0
+        }
+    }
+
+    pub fn apply_to_score(&self, score: i32) -> i32 {
+        (score as f32 * self.score_multiplier()).round() as i32
+    }
+}
+
+// Components
+#[derive(Component)]
+struct AssistsText;
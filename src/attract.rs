@@ -0,0 +1,221 @@
+use bevy::input::gamepad::GamepadButton;
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::asset_loader::UiAssets;
+use crate::autopilot::{Autopilot, AutopilotTarget};
+use crate::game::Resettable;
+use crate::localization::{Localization, LocalizedText};
+use crate::menu::{DrawBlinkTimer, SECONDARY_COLOR};
+use crate::state::{AppState, GameState};
+use crate::MAIN_TITLE;
+
+const MENU_IDLE_SECONDS: f32 = 20.0;
+const DEMO_CONTINUE_SECONDS: f32 = 3.0;
+const DEMO_PLATFORM_FACTORS: [i32; 3] = [2, 5, 10];
+
+pub struct AttractModePlugin;
+
+impl Plugin for AttractModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AttractMode>()
+            .add_systems(OnEnter(AppState::Menu), stop_attract_mode_system)
+            .add_systems(
+                OnEnter(AppState::Game),
+                spawn_attract_mode_overlay_system.run_if(attract_mode_active),
+            )
+            .add_systems(
+                OnEnter(GameState::Setup),
+                choose_demo_platform_system.run_if(attract_mode_active),
+            )
+            .add_systems(
+                Update,
+                (
+                    start_attract_mode_when_idle_system.run_if(in_state(AppState::Menu)),
+                    (
+                        leave_attract_mode_on_any_input_system,
+                        continue_demo_after_landing_system.run_if(in_state(GameState::Landed)),
+                        leave_demo_after_game_over_system.run_if(in_state(GameState::GameOver)),
+                    )
+                        .run_if(in_state(AppState::Game))
+                        .run_if(attract_mode_active),
+                ),
+            );
+    }
+}
+
+// Conditions
+pub fn attract_mode_active(attract_mode: Res<AttractMode>) -> bool {
+    attract_mode.active
+}
+
+// Systems
+fn start_attract_mode_when_idle_system(
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    touches: Res<Touches>,
+    mut autopilot: ResMut<Autopilot>,
+    mut attract_mode: ResMut<AttractMode>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if any_input(&keys, &mouse_buttons, &gamepad_buttons, &touches) {
+        attract_mode.idle_timer.reset();
+        return;
+    }
+    if !attract_mode.idle_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    info!("Menu has been idle for a while, starting attract mode demo");
+    attract_mode.active = true;
+    attract_mode.saved_autopilot = Some(autopilot.clone());
+    autopilot.enabled = true;
+    next_app_state.set(AppState::Game);
+}
+
+fn stop_attract_mode_system(
+    mut attract_mode: ResMut<AttractMode>,
+    mut autopilot: ResMut<Autopilot>,
+) {
+    attract_mode.idle_timer.reset();
+    attract_mode.continue_timer.reset();
+    if !attract_mode.active {
+        return;
+    }
+    attract_mode.active = false;
+    if let Some(saved_autopilot) = attract_mode.saved_autopilot.take() {
+        *autopilot = saved_autopilot;
+    }
+}
+
+fn choose_demo_platform_system(mut autopilot: ResMut<Autopilot>) {
+    let factor = DEMO_PLATFORM_FACTORS
+        .choose(&mut thread_rng())
+        .copied()
+        .unwrap_or(DEMO_PLATFORM_FACTORS[0]);
+    autopilot.target = AutopilotTarget::Platform(factor);
+}
+
+fn spawn_attract_mode_overlay_system(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    localization: Res<Localization>,
+) {
+    commands
+        .spawn((
+            StateScoped(AppState::Game),
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(60.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                MAIN_TITLE,
+                TextStyle {
+                    font: assets.font_kenvector.clone(),
+                    font_size: 120.0,
+                    color: SECONDARY_COLOR,
+                },
+            ));
+            parent.spawn((
+                LocalizedText("attract.demo"),
+                TextBundle::from_section(
+                    localization.text("attract.demo"),
+                    TextStyle {
+                        font: assets.font_kenvector.clone(),
+                        font_size: 30.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                DrawBlinkTimer(Timer::from_seconds(0.5, TimerMode::Repeating)),
+            ));
+        });
+}
+
+fn leave_attract_mode_on_any_input_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    touches: Res<Touches>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if any_input(&keys, &mouse_buttons, &gamepad_buttons, &touches) {
+        next_app_state.set(AppState::Menu);
+    }
+}
+
+fn continue_demo_after_landing_system(
+    time: Res<Time>,
+    resettable_query: Query<Entity, With<Resettable>>,
+    mut attract_mode: ResMut<AttractMode>,
+    mut commands: Commands,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    if attract_mode
+        .continue_timer
+        .tick(time.delta())
+        .just_finished()
+    {
+        attract_mode.continue_timer.reset();
+        for entity in resettable_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        game_state.set(GameState::Setup);
+    }
+}
+
+fn leave_demo_after_game_over_system(
+    time: Res<Time>,
+    mut attract_mode: ResMut<AttractMode>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if attract_mode
+        .continue_timer
+        .tick(time.delta())
+        .just_finished()
+    {
+        attract_mode.continue_timer.reset();
+        next_app_state.set(AppState::Menu);
+    }
+}
+
+fn any_input(
+    keys: &ButtonInput<KeyCode>,
+    mouse_buttons: &ButtonInput<MouseButton>,
+    gamepad_buttons: &ButtonInput<GamepadButton>,
+    touches: &Touches,
+) -> bool {
+    keys.get_just_pressed().next().is_some()
+        || mouse_buttons.get_just_pressed().next().is_some()
+        || gamepad_buttons.get_just_pressed().next().is_some()
+        || touches.any_just_pressed()
+}
+
+// Resources
+#[derive(Resource)]
+pub struct AttractMode {
+    pub active: bool,
+    idle_timer: Timer,
+    continue_timer: Timer,
+    saved_autopilot: Option<Autopilot>,
+}
+
+impl Default for AttractMode {
+    fn default() -> Self {
+        Self {
+            active: false,
+            idle_timer: Timer::from_seconds(MENU_IDLE_SECONDS, TimerMode::Once),
+            continue_timer: Timer::from_seconds(DEMO_CONTINUE_SECONDS, TimerMode::Once),
+            saved_autopilot: None,
+        }
+    }
+}
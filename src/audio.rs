@@ -1,9 +1,11 @@
 use bevy::audio::PlaybackMode;
 use bevy::prelude::*;
-use leafwing_input_manager::action_state::ActionState;
+use bevy_persistent::Persistent;
 
 use crate::asset_loader::AudioAssets;
 use crate::game::Scores;
+use crate::input_buffer::ThrusterTickInput;
+use crate::settings::Settings;
 use crate::spaceship::{AirScapeSoundEffect, PlayerAction, ThrusterSoundEffect};
 use crate::state::GameState;
 
@@ -50,73 +52,64 @@ fn spawn_audio_entities_system(audio_assets: Res<AudioAssets>, mut commands: Com
     ));
 }
 
+/// Follows the thrusters fired on the last fixed tick, so short taps are heard as they are flown.
 fn play_air_scape_sound_effect_system(
-    scores: ResMut<Scores>,
+    scores: Res<Scores>,
+    settings: Res<Persistent<Settings>>,
+    tick_input: Res<ThrusterTickInput>,
     sound_controller: Query<&AudioSink, With<AirScapeSoundEffect>>,
-    mut controllers: Query<&ActionState<PlayerAction>>,
 ) {
-    for action_state in &mut controllers {
-        if scores.fuel_quantity > 0.0 {
-            if action_state.just_pressed(&PlayerAction::LeftThruster)
-                || action_state.just_pressed(&PlayerAction::RightThruster)
-            {
-                if let Ok(sink) = sound_controller.get_single() {
-                    sink.play();
-                }
-            }
-            if action_state.just_released(&PlayerAction::LeftThruster)
-                || action_state.just_released(&PlayerAction::RightThruster)
-            {
-                if let Ok(sink) = sound_controller.get_single() {
-                    sink.pause();
-                }
-            }
-        } else {
-            if let Ok(sink) = sound_controller.get_single() {
-                sink.pause();
-            }
-        }
+    if !tick_input.is_changed() {
+        return;
+    }
+    let Ok(sink) = sound_controller.get_single() else {
+        return;
+    };
+    let side_thrusters = [PlayerAction::LeftThruster, PlayerAction::RightThruster];
+    if scores.fuel_quantity <= 0.0
+        || !side_thrusters
+            .iter()
+            .any(|action| tick_input.pressed(*action))
+    {
+        sink.pause();
+    } else if side_thrusters
+        .iter()
+        .any(|action| tick_input.tapped(*action))
+    {
+        sink.set_volume(settings.sfx_volume);
+        sink.play();
     }
 }
 
 fn play_thruster_sound_effect_system(
-    scores: ResMut<Scores>,
+    scores: Res<Scores>,
+    settings: Res<Persistent<Settings>>,
+    tick_input: Res<ThrusterTickInput>,
     sound_controller: Query<&AudioSink, With<ThrusterSoundEffect>>,
-    mut controllers: Query<&ActionState<PlayerAction>>,
 ) {
-    for action_state in &mut controllers {
-        if scores.fuel_quantity > 0.0 {
-            if action_state.just_pressed(&PlayerAction::MainThrusterBig) {
-                if let Ok(sink) = sound_controller.get_single() {
-                    sink.set_volume(1.0);
-                    sink.play();
-                }
-            }
-            if action_state.just_pressed(&PlayerAction::MainThrusterMedium) {
-                if let Ok(sink) = sound_controller.get_single() {
-                    sink.set_volume(0.66);
-                    sink.play();
-                }
-            }
-            if action_state.just_pressed(&PlayerAction::MainThrusterSmall) {
-                if let Ok(sink) = sound_controller.get_single() {
-                    sink.set_volume(0.33);
-                    sink.play();
-                }
-            }
-            if action_state.just_released(&PlayerAction::MainThrusterBig)
-                || action_state.just_released(&PlayerAction::MainThrusterMedium)
-                || action_state.just_released(&PlayerAction::MainThrusterSmall)
-            {
-                if let Ok(sink) = sound_controller.get_single() {
-                    sink.pause();
-                }
-            }
-        } else {
-            if let Ok(sink) = sound_controller.get_single() {
-                sink.pause();
-            }
-        }
+    if !tick_input.is_changed() {
+        return;
+    }
+    let Ok(sink) = sound_controller.get_single() else {
+        return;
+    };
+    let main_thrusters = [
+        (PlayerAction::MainThrusterBig, 1.0),
+        (PlayerAction::MainThrusterMedium, 0.66),
+        (PlayerAction::MainThrusterSmall, 0.33),
+    ];
+    if scores.fuel_quantity <= 0.0
+        || !main_thrusters
+            .iter()
+            .any(|(action, _)| tick_input.pressed(*action))
+    {
+        sink.pause();
+    } else if let Some((_, volume)) = main_thrusters
+        .iter()
+        .find(|(action, _)| tick_input.tapped(*action))
+    {
+        sink.set_volume(volume * settings.sfx_volume);
+        sink.play();
     }
 }
 
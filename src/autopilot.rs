@@ -0,0 +1,413 @@
+use avian2d::prelude::{Gravity, GravityScale, LinearVelocity};
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::asset_loader::UiAssets;
+use crate::attract::attract_mode_active;
+use crate::collider::{landscape_world_vertices, max_terrain_height_between, Platform};
+use crate::game::{Scores, WorldBoundsVertices2D};
+use crate::localization::Localization;
+use crate::menu::{no_text_input, MenuAction};
+use crate::movement::{JumpImpulse, BIG_THRUST, MEDIUM_THRUST, SMALL_THRUST};
+use crate::session::GameSession;
+use crate::spaceship::{Player, PlayerAction};
+use crate::state::{AppState, GameState};
+
+pub const LANDER_HALF_HEIGHT: f32 = 36.0;
+const CRUISE_MARGIN: f32 = 70.0;
+const TOUCHDOWN_SPEED: f32 = 12.0;
+const MAX_DESCENT_SPEED: f32 = 80.0;
+const MAX_CLIMB_SPEED: f32 = 60.0;
+const MAX_HORIZONTAL_SPEED: f32 = 150.0;
+const HORIZONTAL_SPEED_TOLERANCE: f32 = 5.0;
+
+pub struct AutopilotPlugin;
+
+impl Plugin for AutopilotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Autopilot>()
+            .add_systems(OnEnter(AppState::Game), spawn_autopilot_text_system)
+            .add_systems(OnEnter(GameState::Setup), clear_autopilot_engaged_system)
+            .add_systems(
+                Update,
+                (
+                    handle_autopilot_keys_system.run_if(no_text_input),
+                    update_autopilot_text_system,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Game)),
+            )
+            .add_systems(
+                Update,
+                latch_autopilot_engaged_system
+                    .run_if(in_state(GameState::Landing))
+                    .run_if(autopilot_enabled)
+                    .run_if(not(attract_mode_active)),
+            )
+            .add_systems(
+                PreUpdate,
+                autopilot_control_system
+                    .in_set(InputManagerSystem::ManualControl)
+                    .run_if(in_state(GameState::Landing))
+                    .run_if(autopilot_enabled),
+            );
+    }
+}
+
+// Conditions
+pub fn autopilot_enabled(autopilot: Res<Autopilot>) -> bool {
+    autopilot.enabled
+}
+
+// Systems
+fn spawn_autopilot_text_system(mut commands: Commands, assets: Res<UiAssets>) {
+    commands.spawn((
+        StateScoped(AppState::Game),
+        AutopilotText,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: assets.font_vt323.clone(),
+                color: Color::srgb(0.77, 0.84, 0.11),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(20.0),
+            ..default()
+        }),
+    ));
+}
+
+fn handle_autopilot_keys_system(
+    menu_action_state: Res<ActionState<MenuAction>>,
+    platforms_query: Query<&Platform>,
+    mut autopilot: ResMut<Autopilot>,
+) {
+    if menu_action_state.just_pressed(&MenuAction::ToggleAutopilot) {
+        autopilot.enabled = !autopilot.enabled;
+        info!("Autopilot enabled: {:?}", autopilot.enabled);
+    }
+    if menu_action_state.just_pressed(&MenuAction::AutopilotTarget) {
+        let mut factors: Vec<i32> = platforms_query
+            .iter()
+            .map(|platform| platform.factor)
+            .collect();
+        factors.sort();
+        autopilot.target = autopilot.target.next(&factors);
+        info!("Autopilot target: {}", autopilot.target.label());
+    }
+}
+
+fn clear_autopilot_engaged_system(mut autopilot: ResMut<Autopilot>) {
+    autopilot.engaged = false;
+}
+
+/// Remembers the autopilot flew this landing attempt, even if turned off before the touchdown.
+fn latch_autopilot_engaged_system(
+    mut autopilot: ResMut<Autopilot>,
+    session: Option<ResMut<GameSession>>,
+) {
+    if !autopilot.engaged {
+        autopilot.engaged = true;
+    }
+    if let Some(mut session) = session.filter(|session| !session.autopilot_used) {
+        session.autopilot_used = true;
+    }
+}
+
+fn update_autopilot_text_system(
+    autopilot: Res<Autopilot>,
+    localization: Res<Localization>,
+    mut text_query: Query<&mut Text, With<AutopilotText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    if !autopilot.is_changed() && !localization.is_changed() && !text.is_added() {
+        return;
+    }
+    text.sections[0].value = if autopilot.enabled {
+        localization.format(
+            "autopilot.enabled",
+            &[&autopilot.target.localized_label(&localization)],
+        )
+    } else {
+        "".into()
+    };
+}
+
+fn autopilot_control_system(
+    autopilot: Res<Autopilot>,
+    scores: Res<Scores>,
+    gravity: Res<Gravity>,
+    fixed_time: Res<Time<Fixed>>,
+    landscape: Res<WorldBoundsVertices2D>,
+    platforms_query: Query<(&Platform, &Transform)>,
+    mut spaceship_query: Query<
+        (
+            &Transform,
+            &LinearVelocity,
+            &GravityScale,
+            &JumpImpulse,
+            &mut ActionState<PlayerAction>,
+        ),
+        With<Player>,
+    >,
+) {
+    let Ok((transform, linear_velocity, gravity_scale, jump_impulse, mut action_state)) =
+        spaceship_query.get_single_mut()
+    else {
+        return;
+    };
+    let telemetry = LanderTelemetry {
+        position: transform.translation.xy(),
+        velocity: linear_velocity.0,
+        gravity: gravity.0.y.abs() * gravity_scale.0,
+        fuel: scores.fuel_quantity,
+    };
+    let pads: Vec<LandingPad> = platforms_query
+        .iter()
+        .map(|(platform, transform)| LandingPad::new(platform, transform))
+        .collect();
+    let Some(pad) = autopilot.target.select(&pads, telemetry.position) else {
+        return;
+    };
+    let model = ThrusterModel {
+        ticks_per_second: 1.0 / fixed_time.timestep().as_secs_f32(),
+        jump_impulse: jump_impulse.0,
+    };
+    let terrain = landscape_world_vertices(&landscape);
+    for action in autopilot_actions(&telemetry, &pad, &terrain, &model) {
+        action_state.press(&action);
+    }
+}
+
+/// Thruster actions that bring the lander over `pad` at a safe altitude and then down
+/// within the landing speed window.
+pub fn autopilot_actions(
+    telemetry: &LanderTelemetry,
+    pad: &LandingPad,
+    terrain: &[Vec2],
+    model: &ThrusterModel,
+) -> Vec<PlayerAction> {
+    let mut actions = vec![];
+    if telemetry.fuel <= 0.0 {
+        return actions;
+    }
+    let position = telemetry.position;
+    let velocity = telemetry.velocity;
+    // horizontal approach, the side thrusters are named after the side they are mounted on
+    let distance_x = pad.center.x - position.x;
+    let desired_velocity_x = (distance_x * 1.2).clamp(-MAX_HORIZONTAL_SPEED, MAX_HORIZONTAL_SPEED);
+    if velocity.x < desired_velocity_x - HORIZONTAL_SPEED_TOLERANCE {
+        actions.push(PlayerAction::LeftThruster);
+    } else if velocity.x > desired_velocity_x + HORIZONTAL_SPEED_TOLERANCE {
+        actions.push(PlayerAction::RightThruster);
+    }
+    // vertical profile, cruise over the terrain until aligned with the pad and then descend
+    let touchdown_y = pad.center.y + LANDER_HALF_HEIGHT;
+    let aligned = distance_x.abs() < (pad.half_width * 0.3).max(8.0) && velocity.x.abs() < 20.0;
+    let desired_velocity_y = if aligned {
+        let height = (position.y - touchdown_y).max(0.0);
+        -(TOUCHDOWN_SPEED + height * 0.5).min(MAX_DESCENT_SPEED)
+    } else {
+        let cruise_y = max_terrain_height_between(terrain, position.x, pad.center.x)
+            .map_or(touchdown_y, |terrain_y| terrain_y + LANDER_HALF_HEIGHT)
+            .max(touchdown_y)
+            + CRUISE_MARGIN;
+        (cruise_y - position.y).clamp(-MAX_DESCENT_SPEED, MAX_CLIMB_SPEED)
+    };
+    let velocity_error = desired_velocity_y - velocity.y;
+    if velocity_error > 0.0 {
+        let needed_acceleration = telemetry.gravity + velocity_error * 8.0;
+        let main_thruster = [
+            (PlayerAction::MainThrusterSmall, SMALL_THRUST),
+            (PlayerAction::MainThrusterMedium, MEDIUM_THRUST),
+            (PlayerAction::MainThrusterBig, BIG_THRUST),
+        ]
+        .into_iter()
+        .find(|(_, thrust)| model.acceleration(*thrust) >= needed_acceleration)
+        .map_or(PlayerAction::MainThrusterBig, |(action, _)| action);
+        actions.push(main_thruster);
+    }
+    actions
+}
+
+// Resources
+#[derive(Resource, Debug, Default, Clone)]
+pub struct Autopilot {
+    pub enabled: bool,
+    pub target: AutopilotTarget,
+    /// Flew at some point of the current landing attempt, which then scores nothing
+    pub engaged: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum AutopilotTarget {
+    #[default]
+    Nearest,
+    Platform(i32),
+}
+
+impl AutopilotTarget {
+    /// Cycles through the nearest pad and every available platform factor.
+    pub fn next(self, factors: &[i32]) -> Self {
+        let next_index = match self {
+            AutopilotTarget::Nearest => 0,
+            AutopilotTarget::Platform(factor) => factors
+                .iter()
+                .position(|current| *current == factor)
+                .map_or(factors.len(), |index| index + 1),
+        };
+        factors
+            .get(next_index)
+            .map_or(AutopilotTarget::Nearest, |factor| {
+                AutopilotTarget::Platform(*factor)
+            })
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            AutopilotTarget::Nearest => "nearest pad".into(),
+            AutopilotTarget::Platform(factor) => format!("x{} pad", factor),
+        }
+    }
+
+    /// Label shown by the autopilot text, `label` is kept in English for logs and benchmarks.
+    pub fn localized_label(&self, localization: &Localization) -> String {
+        match self {
+            AutopilotTarget::Nearest => localization.text("autopilot.nearest_pad").into(),
+            AutopilotTarget::Platform(factor) => {
+                localization.format("autopilot.platform_pad", &[&factor.to_string()])
+            }
+        }
+    }
+
+    pub fn select(&self, pads: &[LandingPad], position: Vec2) -> Option<LandingPad> {
+        match self {
+            AutopilotTarget::Nearest => pads.iter().copied().min_by(|a, b| {
+                a.center
+                    .distance(position)
+                    .total_cmp(&b.center.distance(position))
+            }),
+            AutopilotTarget::Platform(factor) => {
+                pads.iter().copied().find(|pad| pad.factor == *factor)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LanderTelemetry {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    /// Downwards acceleration, already scaled by the lander gravity scale
+    pub gravity: f32,
+    pub fuel: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LandingPad {
+    pub center: Vec2,
+    pub half_width: f32,
+    pub factor: i32,
+}
+
+impl LandingPad {
+    pub fn new(platform: &Platform, transform: &Transform) -> Self {
+        Self {
+            center: transform.translation.xy(),
+            half_width: platform.width / 2.0,
+            factor: platform.factor,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ThrusterModel {
+    pub ticks_per_second: f32,
+    pub jump_impulse: f32,
+}
+
+impl ThrusterModel {
+    /// Upwards acceleration given by a main thruster level, the impulse is applied once per fixed tick.
+    pub fn acceleration(&self, thrust: f32) -> f32 {
+        self.jump_impulse * thrust * self.ticks_per_second
+    }
+}
+
+// Components
+#[derive(Component)]
+struct AutopilotText;
+
+/// Flies a headless episode launched from `seed` with the autopilot aiming at `target`. Returns
+/// the outcome and the factor of the pad chosen at launch.
+#[cfg(test)]
+fn fly_headless(target: AutopilotTarget, seed: u64) -> (Option<crate::environment::Outcome>, i32) {
+    use crate::environment::{LanderEnvironment, TICKS_PER_SECOND};
+    let terrain = landscape_world_vertices(&crate::get_world_bounds_resource_2d());
+    let model = ThrusterModel {
+        ticks_per_second: TICKS_PER_SECOND as f32,
+        jump_impulse: crate::spaceship::LANDER_JUMP_IMPULSE,
+    };
+    let mut environment = LanderEnvironment::default();
+    let mut observation = environment.reset(seed);
+    let mut chosen_factor = None;
+    loop {
+        let telemetry = LanderTelemetry {
+            position: observation.position,
+            velocity: observation.velocity,
+            gravity: crate::GRAVITY * observation.gravity_scale,
+            fuel: observation.fuel,
+        };
+        let pads: Vec<LandingPad> = observation
+            .platforms
+            .iter()
+            .map(|platform| LandingPad {
+                center: observation.position + platform.offset,
+                half_width: platform.half_width,
+                factor: platform.factor,
+            })
+            .collect();
+        let pad = target.select(&pads, telemetry.position).unwrap();
+        let chosen_factor = *chosen_factor.get_or_insert(pad.factor);
+        let step = environment.step(&autopilot_actions(&telemetry, &pad, &terrain, &model));
+        if step.done {
+            return (step.info.outcome, chosen_factor);
+        }
+        observation = step.observation;
+    }
+}
+
+#[test]
+fn autopilot_lands_on_the_nearest_pad_test() {
+    let (outcome, chosen_factor) = fly_headless(AutopilotTarget::Nearest, 42);
+    assert!(
+        matches!(
+            outcome,
+            Some(crate::environment::Outcome::Landed { platform_factor, .. })
+                if platform_factor == chosen_factor
+        ),
+        "{:?}",
+        outcome
+    );
+}
+
+#[test]
+fn autopilot_lands_on_the_target_platform_test() {
+    for factor in [2, 5, 10] {
+        let (outcome, _) = fly_headless(AutopilotTarget::Platform(factor), 7);
+        assert!(
+            matches!(
+                outcome,
+                Some(crate::environment::Outcome::Landed { platform_factor, .. })
+                    if platform_factor == factor
+            ),
+            "{:?}",
+            outcome
+        );
+    }
+}
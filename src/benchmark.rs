@@ -0,0 +1,404 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use crate::autopilot::{
+    autopilot_actions, AutopilotTarget, LanderTelemetry, LandingPad, ThrusterModel,
+};
+use crate::collider::landscape_world_vertices;
+use crate::control_server::{
+    ClientCommand, ControlEndpoint, ControlServer, LanderState, ServerMessage,
+};
+use crate::environment::{
+    EnvironmentConfig, LanderEnvironment, Observation, Outcome, TICKS_PER_SECOND,
+};
+use crate::explosion::CrashCause;
+use crate::spaceship::{PlayerAction, LANDER_JUMP_IMPULSE};
+use crate::state::GameState;
+use crate::{get_world_bounds_resource_2d, GRAVITY};
+
+/// Time a socket bot has to answer every lander state.
+const BOT_ANSWER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Anything able to fly the lander one fixed tick at a time.
+pub trait LandingController {
+    fn name(&self) -> String;
+
+    fn begin_episode(&mut self, _seed: u64) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn actions(&mut self, observation: &Observation, tick: u32) -> io::Result<Vec<PlayerAction>>;
+
+    fn end_episode(&mut self, _outcome: Outcome) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Runs `episodes` episodes seeded from `first_seed` onwards.
+pub fn run_benchmark(
+    config: EnvironmentConfig,
+    controller: &mut dyn LandingController,
+    episodes: u32,
+    first_seed: u64,
+) -> io::Result<BenchmarkSummary> {
+    let mut environment = LanderEnvironment::new(config);
+    let mut results = vec![];
+    for episode in 0..episodes {
+        let seed = first_seed + episode as u64;
+        controller.begin_episode(seed)?;
+        let mut observation = environment.reset(seed);
+        let mut fuel_used = 0.0;
+        let mut tick = 0;
+        let outcome = loop {
+            let actions = controller.actions(&observation, tick)?;
+            let step = environment.step(&actions);
+            tick += 1;
+            fuel_used += step.info.fuel_used;
+            observation = step.observation;
+            if let Some(outcome) = step.info.outcome {
+                break outcome;
+            }
+        };
+        controller.end_episode(outcome)?;
+        results.push(EpisodeResult {
+            seed,
+            outcome,
+            fuel_used,
+            seconds: tick as f32 / TICKS_PER_SECOND as f32,
+        });
+    }
+    Ok(BenchmarkSummary::new(controller.name(), config, &results))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EpisodeResult {
+    pub seed: u64,
+    pub outcome: Outcome,
+    pub fuel_used: f32,
+    pub seconds: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkSummary {
+    pub controller: String,
+    pub gravity_scale: f32,
+    pub fuel: f32,
+    pub episodes: u32,
+    pub landings: u32,
+    pub success_rate: f32,
+    /// Landing score averaged over every episode, crashes count as zero
+    pub mean_score: f32,
+    pub mean_fuel_used: f32,
+    /// Flight time averaged over the successful landings only
+    pub mean_time_to_land: Option<f32>,
+    pub crash_causes: BTreeMap<String, u32>,
+    pub time_limits: u32,
+}
+
+impl BenchmarkSummary {
+    pub fn new(controller: String, config: EnvironmentConfig, results: &[EpisodeResult]) -> Self {
+        let episodes = results.len() as u32;
+        let mut landings = 0;
+        let mut total_score = 0;
+        let mut total_time_to_land = 0.0;
+        let mut time_limits = 0;
+        let mut crash_causes: BTreeMap<String, u32> = [
+            CrashCause::TooFast,
+            CrashCause::OffPlatform,
+            CrashCause::Terrain,
+            CrashCause::WorldBounds,
+        ]
+        .into_iter()
+        .map(|cause| (crash_cause_key(cause), 0))
+        .collect();
+        for result in results {
+            match result.outcome {
+                Outcome::Landed { score, .. } => {
+                    landings += 1;
+                    total_score += score;
+                    total_time_to_land += result.seconds;
+                }
+                Outcome::Crashed { cause } => {
+                    *crash_causes.entry(crash_cause_key(cause)).or_default() += 1;
+                }
+                Outcome::TimeLimit => time_limits += 1,
+            }
+        }
+        let per_episode = |total: f32| {
+            if episodes == 0 {
+                0.0
+            } else {
+                total / episodes as f32
+            }
+        };
+        Self {
+            controller,
+            gravity_scale: config.gravity_scale,
+            fuel: config.fuel,
+            episodes,
+            landings,
+            success_rate: per_episode(landings as f32),
+            mean_score: per_episode(total_score as f32),
+            mean_fuel_used: per_episode(results.iter().map(|result| result.fuel_used).sum()),
+            mean_time_to_land: (landings > 0).then(|| total_time_to_land / landings as f32),
+            crash_causes,
+            time_limits,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("failed to serialize the benchmark summary")
+    }
+
+    /// Header and a single row, crash causes get a column each.
+    pub fn to_csv(&self) -> String {
+        let mut header = vec![
+            "controller",
+            "gravity_scale",
+            "fuel",
+            "episodes",
+            "landings",
+            "success_rate",
+            "mean_score",
+            "mean_fuel_used",
+            "mean_time_to_land",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<_>>();
+        let mut row = vec![
+            self.controller.replace(',', ";"),
+            self.gravity_scale.to_string(),
+            self.fuel.to_string(),
+            self.episodes.to_string(),
+            self.landings.to_string(),
+            self.success_rate.to_string(),
+            self.mean_score.to_string(),
+            self.mean_fuel_used.to_string(),
+            self.mean_time_to_land
+                .map_or(String::new(), |seconds| seconds.to_string()),
+        ];
+        for (cause, count) in self.crash_causes.iter() {
+            header.push(format!("crashed_{}", cause));
+            row.push(count.to_string());
+        }
+        header.push("time_limits".into());
+        row.push(self.time_limits.to_string());
+        format!("{}\n{}\n", header.join(","), row.join(","))
+    }
+}
+
+fn crash_cause_key(cause: CrashCause) -> String {
+    match cause {
+        CrashCause::TooFast => "too_fast",
+        CrashCause::OffPlatform => "off_platform",
+        CrashCause::Terrain => "terrain",
+        CrashCause::WorldBounds => "world_bounds",
+    }
+    .into()
+}
+
+// Controllers
+/// The in game autopilot, flying from the environment observations.
+pub struct AutopilotController {
+    pub target: AutopilotTarget,
+    terrain: Vec<Vec2>,
+}
+
+impl AutopilotController {
+    pub fn new(target: AutopilotTarget) -> Self {
+        Self {
+            target,
+            terrain: landscape_world_vertices(&get_world_bounds_resource_2d()),
+        }
+    }
+}
+
+impl LandingController for AutopilotController {
+    fn name(&self) -> String {
+        format!("autopilot {}", self.target.label())
+    }
+
+    fn actions(&mut self, observation: &Observation, _tick: u32) -> io::Result<Vec<PlayerAction>> {
+        let telemetry = LanderTelemetry {
+            position: observation.position,
+            velocity: observation.velocity,
+            gravity: GRAVITY * observation.gravity_scale,
+            fuel: observation.fuel,
+        };
+        let pads: Vec<LandingPad> = observation
+            .platforms
+            .iter()
+            .map(|platform| LandingPad {
+                center: observation.position + platform.offset,
+                half_width: platform.half_width,
+                factor: platform.factor,
+            })
+            .collect();
+        let Some(pad) = self.target.select(&pads, telemetry.position) else {
+            return Ok(vec![]);
+        };
+        let model = ThrusterModel {
+            ticks_per_second: TICKS_PER_SECOND as f32,
+            jump_impulse: LANDER_JUMP_IMPULSE,
+        };
+        Ok(autopilot_actions(&telemetry, &pad, &self.terrain, &model))
+    }
+}
+
+/// Replays the same recorded actions every episode, a JSON array with the thrusters held on every tick,
+/// like `[["MainThrusterBig"], [], ["LeftThruster", "MainThrusterSmall"]]`.
+pub struct ReplayController {
+    name: String,
+    ticks: Vec<Vec<PlayerAction>>,
+}
+
+impl ReplayController {
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let ticks = serde_json::from_str(&content)
+            .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?;
+        Ok(Self {
+            name: format!("replay {}", path.display()),
+            ticks,
+        })
+    }
+}
+
+impl LandingController for ReplayController {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn actions(&mut self, _observation: &Observation, tick: u32) -> io::Result<Vec<PlayerAction>> {
+        Ok(self.ticks.get(tick as usize).cloned().unwrap_or_default())
+    }
+}
+
+/// An external bot speaking the control server protocol. The simulation waits for an `actions`
+/// command after every streamed state, so slow bots get the same results as fast ones.
+pub struct SocketController {
+    name: String,
+    server: ControlServer,
+}
+
+impl SocketController {
+    /// Binds `endpoint` and waits for the first bot to connect.
+    pub fn connect(endpoint: &ControlEndpoint) -> io::Result<Self> {
+        let mut server = ControlServer::bind(endpoint)?;
+        info!("Waiting for a bot on {:?}", endpoint);
+        while server.client_count() == 0 {
+            server.poll();
+            sleep(Duration::from_millis(10));
+        }
+        Ok(Self {
+            name: format!("socket bot {:?}", endpoint),
+            server,
+        })
+    }
+}
+
+impl LandingController for SocketController {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn actions(&mut self, observation: &Observation, tick: u32) -> io::Result<Vec<PlayerAction>> {
+        self.server.broadcast(&ServerMessage::State(LanderState {
+            tick: tick as u64,
+            state: GameState::Landing,
+            position: observation.position,
+            velocity: observation.velocity,
+            fuel: observation.fuel,
+            gravity_scale: observation.gravity_scale,
+            score: 0,
+            ready_to_land: observation.ready_to_land,
+        }));
+        let started = Instant::now();
+        while started.elapsed() < BOT_ANSWER_TIMEOUT {
+            if self.server.client_count() == 0 {
+                return Err(io::Error::new(
+                    ErrorKind::ConnectionAborted,
+                    "the bot disconnected",
+                ));
+            }
+            let actions = self
+                .server
+                .poll()
+                .into_iter()
+                .filter_map(|command| match command {
+                    ClientCommand::Actions { actions } => Some(actions),
+                    _ => None,
+                })
+                .last();
+            if let Some(actions) = actions {
+                return Ok(actions);
+            }
+            sleep(Duration::from_micros(200));
+        }
+        Err(io::Error::new(
+            ErrorKind::TimedOut,
+            "the bot did not send its actions in time",
+        ))
+    }
+
+    fn end_episode(&mut self, outcome: Outcome) -> io::Result<()> {
+        match outcome {
+            Outcome::Landed {
+                platform_factor,
+                vertical_velocity,
+                score,
+            } => self.server.broadcast(&ServerMessage::Landed {
+                platform_factor,
+                vertical_velocity,
+                score,
+            }),
+            Outcome::Crashed { cause } => self.server.broadcast(&ServerMessage::Crashed { cause }),
+            Outcome::TimeLimit => {}
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn benchmark_summary_test() {
+    let results = [
+        EpisodeResult {
+            seed: 0,
+            outcome: Outcome::Landed {
+                platform_factor: 5,
+                vertical_velocity: -10.0,
+                score: 2875,
+            },
+            fuel_used: 100.0,
+            seconds: 8.0,
+        },
+        EpisodeResult {
+            seed: 1,
+            outcome: Outcome::Crashed {
+                cause: CrashCause::TooFast,
+            },
+            fuel_used: 50.0,
+            seconds: 4.0,
+        },
+    ];
+    let summary = BenchmarkSummary::new("test".into(), EnvironmentConfig::default(), &results);
+    assert_eq!(summary.landings, 1);
+    assert_eq!(summary.success_rate, 0.5);
+    assert_eq!(summary.mean_score, 1437.5);
+    assert_eq!(summary.mean_fuel_used, 75.0);
+    assert_eq!(summary.mean_time_to_land, Some(8.0));
+    assert_eq!(summary.crash_causes["too_fast"], 1);
+    assert_eq!(summary.crash_causes["terrain"], 0);
+    let csv = summary.to_csv();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next().unwrap().split(',').count(),
+        lines.next().unwrap().split(',').count()
+    );
+}
@@ -0,0 +1,104 @@
+//! Runs seeded headless episodes with a landing controller and prints a JSON or CSV summary.
+//!
+//! cargo run --release --bin benchmark -- --episodes 100 --controller autopilot --format csv
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use rusty_lander::autopilot::AutopilotTarget;
+use rusty_lander::benchmark::{
+    run_benchmark, AutopilotController, LandingController, ReplayController, SocketController,
+};
+use rusty_lander::environment::EnvironmentConfig;
+
+const USAGE: &str =
+    "usage: benchmark [--episodes N] [--seed FIRST_SEED] [--gravity SCALE] [--fuel FUEL]
+                 [--max-steps TICKS] [--format json|csv] [--output FILE]
+                 [--controller autopilot[:FACTOR] | replay:FILE | socket:ENDPOINT]";
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1).collect()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(arguments: Vec<String>) -> Result<(), String> {
+    let mut config = EnvironmentConfig::default();
+    let mut episodes = 100;
+    let mut first_seed = 0;
+    let mut controller = "autopilot".to_string();
+    let mut format = "json".to_string();
+    let mut output: Option<PathBuf> = None;
+    let mut arguments = arguments.into_iter();
+    while let Some(argument) = arguments.next() {
+        let mut value = || {
+            arguments
+                .next()
+                .ok_or_else(|| format!("missing value for {}", argument))
+        };
+        match argument.as_str() {
+            "--episodes" => episodes = parse(&value()?)?,
+            "--seed" => first_seed = parse(&value()?)?,
+            "--gravity" => config.gravity_scale = parse(&value()?)?,
+            "--fuel" => config.fuel = parse(&value()?)?,
+            "--max-steps" => config.max_steps = parse(&value()?)?,
+            "--controller" => controller = value()?,
+            "--format" => format = value()?,
+            "--output" => output = Some(PathBuf::from(value()?)),
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ => return Err(format!("unknown argument {}", argument)),
+        }
+    }
+    let mut controller = build_controller(&controller)?;
+    let summary = run_benchmark(config, controller.as_mut(), episodes, first_seed)
+        .map_err(|error| format!("benchmark aborted: {}", error))?;
+    let report = match format.as_str() {
+        "json" => summary.to_json() + "\n",
+        "csv" => summary.to_csv(),
+        _ => return Err(format!("unknown format {}", format)),
+    };
+    match output {
+        Some(path) => std::fs::write(&path, report)
+            .map_err(|error| format!("unable to write {}: {}", path.display(), error)),
+        None => {
+            print!("{}", report);
+            Ok(())
+        }
+    }
+}
+
+fn build_controller(controller: &str) -> Result<Box<dyn LandingController>, String> {
+    let (kind, parameter) = controller
+        .split_once(':')
+        .map_or((controller, None), |(kind, parameter)| {
+            (kind, Some(parameter))
+        });
+    match (kind, parameter) {
+        ("autopilot", None) => Ok(Box::new(AutopilotController::new(AutopilotTarget::Nearest))),
+        ("autopilot", Some(factor)) => Ok(Box::new(AutopilotController::new(
+            AutopilotTarget::Platform(parse(factor)?),
+        ))),
+        ("replay", Some(path)) => ReplayController::from_file(path.as_ref())
+            .map(|controller| Box::new(controller) as Box<dyn LandingController>)
+            .map_err(|error| format!("unable to load the replay {}: {}", path, error)),
+        ("socket", Some(endpoint)) => endpoint
+            .parse()
+            .and_then(|endpoint| SocketController::connect(&endpoint))
+            .map(|controller| Box::new(controller) as Box<dyn LandingController>)
+            .map_err(|error| format!("unable to listen on {}: {}", endpoint, error)),
+        _ => Err(format!("unknown controller {}", controller)),
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value {}", value))
+}
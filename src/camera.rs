@@ -1,9 +1,14 @@
 use crate::collider::{PLATFORM_10X_CENTER, PLATFORM_2X_CENTER, PLATFORM_5X_CENTER};
 use crate::game::InGameSet;
 use crate::gizmos::PROXIMITY_RADIUS;
+use crate::radar::RadarAltimeter;
+use crate::settings::Settings;
 use crate::spaceship::Player;
+use crate::{WINDOW_HEIGHT, WINDOW_WIDTH};
 use bevy::prelude::*;
+use bevy::render::camera::ScalingMode;
 use bevy::render::view::RenderLayers;
+use bevy_persistent::Persistent;
 use iyes_perf_ui::prelude::*;
 use std::ops::Add;
 
@@ -33,52 +38,73 @@ impl Plugin for CameraPlugin {
 
 // Systems
 fn spawn_camera_system(mut commands: Commands) {
+    // clears the whole window, the bars left around the letterboxed play area stay black
     commands.spawn((
-        GameCamera,
         Camera2dBundle {
             camera: Camera {
-                order: 1,
+                order: 0,
+                clear_color: ClearColorConfig::Custom(Color::BLACK),
                 ..default()
             },
             ..default()
         },
+        RenderLayers::none(),
+    ));
+    commands.spawn((
+        GameCamera,
+        play_area_camera_bundle(1),
         RenderLayers::from_layers(&[0, 1]),
     ));
     commands.spawn((
         ControllersCamera,
-        Camera2dBundle {
-            camera: Camera {
-                order: 2,
-                ..default()
-            },
-            ..default()
-        },
+        play_area_camera_bundle(2),
         RenderLayers::from_layers(&[1, 2]),
     ));
 }
 
+/// Camera showing the whole play area whatever the window size, its viewport is kept by the
+/// display plugin.
+fn play_area_camera_bundle(order: isize) -> Camera2dBundle {
+    let mut camera_bundle = Camera2dBundle {
+        camera: Camera { order, ..default() },
+        ..default()
+    };
+    camera_bundle.projection.scaling_mode = ScalingMode::Fixed {
+        width: WINDOW_WIDTH,
+        height: WINDOW_HEIGHT,
+    };
+    camera_bundle
+}
+
 fn add_or_remove_player_camera_components_depending_on_nearest_platform_system(
     mut commands: Commands,
-    mut spaceship_query: Query<(Entity, &Transform), With<Player>>,
+    settings: Res<Persistent<Settings>>,
+    mut spaceship_query: Query<(Entity, &RadarAltimeter), With<Player>>,
 ) {
-    let Ok((entity, transform)) = spaceship_query.get_single_mut() else {
+    let Ok((entity, radar_altimeter)) = spaceship_query.get_single_mut() else {
         return;
     };
-    if transform.translation.distance(PLATFORM_2X_CENTER) < PROXIMITY_RADIUS {
+    // zoom in when the radar sees a platform right below, close enough, unless camera snaps are
+    // disabled, which also zooms back out when disabled while zoomed in
+    let platform_below = radar_altimeter
+        .platform_below()
+        .filter(|_| settings.accessibility.camera_snaps)
+        .filter(|_| radar_altimeter.height() < Some(PROXIMITY_RADIUS));
+    if platform_below == Some(2) {
         commands.entity(entity).insert(GameCameraCloseTo2xPlatform);
     } else {
         commands
             .entity(entity)
             .remove::<GameCameraCloseTo2xPlatform>();
     }
-    if transform.translation.distance(PLATFORM_5X_CENTER) < PROXIMITY_RADIUS {
+    if platform_below == Some(5) {
         commands.entity(entity).insert(GameCameraCloseTo5xPlatform);
     } else {
         commands
             .entity(entity)
             .remove::<GameCameraCloseTo5xPlatform>();
     }
-    if transform.translation.distance(PLATFORM_10X_CENTER) < PROXIMITY_RADIUS {
+    if platform_below == Some(10) {
         commands.entity(entity).insert(GameCameraCloseTo10xPlatform);
     } else {
         commands
@@ -155,7 +181,7 @@ fn spawn_debug_ui_system(mut commands: Commands) {
 pub struct GameCamera;
 
 #[derive(Component)]
-struct ControllersCamera;
+pub struct ControllersCamera;
 
 #[derive(Component)]
 #[component(storage = "SparseSet")]
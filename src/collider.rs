@@ -1,12 +1,13 @@
 use avian2d::math::Vector;
 use avian2d::prelude::*;
 use bevy::color::palettes::css;
-use bevy::sprite::MaterialMesh2dBundle;
 use bevy::{ecs::query::Has, prelude::*};
 
 use crate::asset_loader::SceneAssets;
-use crate::explosion::SpawnExplosionEvent;
-use crate::game::{InGameSet, SpaceshipJustLandedEvent, WorldBoundsVertices2D};
+use crate::assists::Assists;
+use crate::autopilot::Autopilot;
+use crate::explosion::{CrashCause, SpawnExplosionEvent};
+use crate::game::{awarded_landing_score, SpaceshipJustLandedEvent, WorldBoundsVertices2D};
 use crate::movement::ReadyToLand;
 use crate::spaceship::Player;
 use crate::state::{AppState, GameState};
@@ -14,89 +15,28 @@ use crate::state::{AppState, GameState};
 pub const PLATFORM_2X_CENTER: Vec3 = Vec3::new(114.0, 164.0, 1.0);
 pub const PLATFORM_5X_CENTER: Vec3 = Vec3::new(-287.0, -222.0, 1.0);
 pub const PLATFORM_10X_CENTER: Vec3 = Vec3::new(230.0, -104.0, 1.0);
+pub const LANDSCAPE_TRANSLATION: Vec2 = Vec2::new(-513.0, 306.0);
+pub const LANDSCAPE_SCALE: Vec2 = Vec2::new(1.45, 1.46);
+pub const WORLD_BOUNDS_MIN_X: f32 = -547.0;
+pub const WORLD_BOUNDS_MAX_X: f32 = 482.0;
+pub const WORLD_BOUNDS_MIN_Y: f32 = -300.0;
+pub const WORLD_BOUNDS_MAX_Y: f32 = 10360.0;
 
 pub struct ColliderPlugin;
 
 impl Plugin for ColliderPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Game), initialize_landscape_system)
-            .add_systems(
-                FixedUpdate,
-                player_landed_collisions_system
-                    .run_if(in_state(GameState::Landing))
-                    .in_set(InGameSet::Collisions),
-            );
+        app.add_systems(OnEnter(AppState::Game), initialize_landscape_system);
     }
 }
 
 // Systems
 fn initialize_landscape_system(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
     landscape_world_bounds_vertices_2d: Res<WorldBoundsVertices2D>,
     scene_assets: Res<SceneAssets>,
 ) {
-    // world bounds collider
-    let world_bounds_vertices = vec![
-        Vector::new(-547.0, 10360.0),
-        Vector::new(-547.0, -300.0),
-        Vector::new(482.0, -300.0),
-        Vector::new(482.0, 10360.0),
-    ];
-    let world_bounds_polyline = Collider::polyline(world_bounds_vertices, None);
-    commands.spawn((
-        StateScoped(AppState::Game),
-        RigidBody::Static,
-        Sensor,
-        world_bounds_polyline,
-        DebugRender::default().with_collider_color(css::INDIAN_RED.into()),
-    ));
-    // platform x2
-    commands.spawn((
-        StateScoped(AppState::Game),
-        Collider::rectangle(185.0, 8.0),
-        RigidBody::Static,
-        Sensor,
-        MaterialMesh2dBundle {
-            mesh: meshes.add(Rectangle::new(185.0, 8.0)).into(),
-            material: materials.add(Color::srgba(0.3, 0.3, 0.3, 0.0)),
-            transform: Transform::from_translation(PLATFORM_2X_CENTER),
-            ..default()
-        },
-        Platform { factor: 2 },
-        DebugRender::default().with_collider_color(css::SPRING_GREEN.into()),
-    ));
-    // platform x5
-    commands.spawn((
-        StateScoped(AppState::Game),
-        Collider::rectangle(200.0, 8.0),
-        RigidBody::Static,
-        Sensor,
-        MaterialMesh2dBundle {
-            mesh: meshes.add(Rectangle::new(200.0, 8.0)).into(),
-            material: materials.add(Color::srgba(0.3, 0.3, 0.3, 0.0)),
-            transform: Transform::from_translation(PLATFORM_5X_CENTER),
-            ..default()
-        },
-        Platform { factor: 5 },
-        DebugRender::default().with_collider_color(css::SPRING_GREEN.into()),
-    ));
-    // platform x10
-    commands.spawn((
-        StateScoped(AppState::Game),
-        Collider::rectangle(120.0, 8.0),
-        RigidBody::Static,
-        Sensor,
-        MaterialMesh2dBundle {
-            mesh: meshes.add(Rectangle::new(120.0, 8.0)).into(),
-            material: materials.add(Color::srgba(0.3, 0.3, 0.3, 0.0)),
-            transform: Transform::from_translation(PLATFORM_10X_CENTER),
-            ..default()
-        },
-        Platform { factor: 10 },
-        DebugRender::default().with_collider_color(css::SPRING_GREEN.into()),
-    ));
+    spawn_landscape_colliders(&mut commands, &landscape_world_bounds_vertices_2d);
     // land image
     let sprite_image_handle = scene_assets.landscape.clone();
     commands.spawn((
@@ -111,6 +51,45 @@ fn initialize_landscape_system(
             ..default()
         },
     ));
+}
+
+/// Spawns the world bounds, the platforms and the landscape colliders.
+pub fn spawn_landscape_colliders(
+    commands: &mut Commands,
+    landscape_world_bounds_vertices_2d: &WorldBoundsVertices2D,
+) {
+    // world bounds collider
+    let world_bounds_vertices = vec![
+        Vector::new(WORLD_BOUNDS_MIN_X, WORLD_BOUNDS_MAX_Y),
+        Vector::new(WORLD_BOUNDS_MIN_X, WORLD_BOUNDS_MIN_Y),
+        Vector::new(WORLD_BOUNDS_MAX_X, WORLD_BOUNDS_MIN_Y),
+        Vector::new(WORLD_BOUNDS_MAX_X, WORLD_BOUNDS_MAX_Y),
+    ];
+    let world_bounds_polyline = Collider::polyline(world_bounds_vertices, None);
+    commands.spawn((
+        StateScoped(AppState::Game),
+        RigidBody::Static,
+        Sensor,
+        world_bounds_polyline,
+        WorldBounds,
+        DebugRender::default().with_collider_color(css::INDIAN_RED.into()),
+    ));
+    // platforms
+    for (center, factor, width) in [
+        (PLATFORM_2X_CENTER, 2, 185.0),
+        (PLATFORM_5X_CENTER, 5, 200.0),
+        (PLATFORM_10X_CENTER, 10, 120.0),
+    ] {
+        commands.spawn((
+            StateScoped(AppState::Game),
+            Collider::rectangle(width, 8.0),
+            RigidBody::Static,
+            Sensor,
+            TransformBundle::from_transform(Transform::from_translation(center)),
+            Platform { factor, width },
+            DebugRender::default().with_collider_color(css::SPRING_GREEN.into()),
+        ));
+    }
     // land collider
     let collider = Collider::polyline(landscape_world_bounds_vertices_2d.data.clone(), None);
     commands.spawn((
@@ -118,19 +97,16 @@ fn initialize_landscape_system(
         collider,
         RigidBody::Static,
         Sensor,
-        SpriteBundle {
-            transform: Transform {
-                translation: Vec3::new(-513.0, 306.0, 1.0),
-                scale: Vec3::new(1.45, 1.46, 1.0),
-                ..default()
-            },
+        TransformBundle::from_transform(Transform {
+            translation: LANDSCAPE_TRANSLATION.extend(1.0),
+            scale: LANDSCAPE_SCALE.extend(1.0),
             ..default()
-        },
+        }),
         DebugRender::default().with_collider_color(css::STEEL_BLUE.into()),
     ));
 }
 
-fn player_landed_collisions_system(
+pub fn player_landed_collisions_system(
     query: Query<
         (
             &CollidingEntities,
@@ -141,51 +117,111 @@ fn player_landed_collisions_system(
         With<Player>,
     >,
     platforms_query: Query<&Platform>,
+    world_bounds_query: Query<(), With<WorldBounds>>,
+    assists: Option<Res<Assists>>,
+    autopilot: Option<Res<Autopilot>>,
     mut game_state: ResMut<NextState<GameState>>,
     mut explosion_spawn_events: EventWriter<SpawnExplosionEvent>,
     mut spaceship_just_landed_spawn_events: EventWriter<SpaceshipJustLandedEvent>,
     mut physics_time: ResMut<Time<Physics>>,
 ) {
     for (colliding_entities, &linear_velocity, transform, is_ready_to_land) in &query {
-        if !colliding_entities.is_empty() {
-            physics_time.pause();
-            if !is_ready_to_land {
-                info!("Lander is not ready to land. Crash!");
+        let Some(&colliding_entity) = colliding_entities.iter().next() else {
+            continue;
+        };
+        physics_time.pause();
+        let platform = platforms_query.get(colliding_entity).ok();
+        let crash_cause = if world_bounds_query.contains(colliding_entity) {
+            Some(CrashCause::WorldBounds)
+        } else {
+            match (platform, is_ready_to_land) {
+                (Some(_), true) => None,
+                (Some(_), false) => Some(CrashCause::TooFast),
+                (None, true) => Some(CrashCause::OffPlatform),
+                (None, false) => Some(CrashCause::Terrain),
+            }
+        };
+        match (crash_cause, platform) {
+            (None, Some(platform)) => {
+                info!(
+                    "Landed in platform factor {:?} with linear velocity {:?}",
+                    platform.factor, linear_velocity.y
+                );
+                let autopilot_engaged = autopilot
+                    .as_ref()
+                    .is_some_and(|autopilot| autopilot.engaged);
+                spaceship_just_landed_spawn_events.send(SpaceshipJustLandedEvent {
+                    platform: platform.clone(),
+                    linear_velocity,
+                    autopilot: autopilot_engaged,
+                    score: awarded_landing_score(
+                        platform.factor,
+                        linear_velocity.y,
+                        assists.as_deref(),
+                        autopilot_engaged,
+                    ),
+                });
+                game_state.set(GameState::Landed);
+            }
+            (cause, _) => {
+                let cause = cause.unwrap_or(CrashCause::Terrain);
+                info!("Lander crashed: {:?}", cause);
                 explosion_spawn_events.send(SpawnExplosionEvent {
                     x: transform.translation.x,
                     y: transform.translation.y,
+                    cause,
                 });
                 game_state.set(GameState::Crashed);
-                break;
-            } else {
-                if let Some(&colliding_entity) = colliding_entities.iter().next() {
-                    if let Ok(platform) = platforms_query.get(colliding_entity) {
-                        info!(
-                            "Landed in platform factor {:?} with linear velocity {:?}",
-                            platform.factor, linear_velocity.y
-                        );
-                        spaceship_just_landed_spawn_events.send(SpaceshipJustLandedEvent {
-                            platform: platform.clone(),
-                            linear_velocity,
-                        });
-                        game_state.set(GameState::Landed);
-                    } else {
-                        info!("Landed outside a platform");
-                        explosion_spawn_events.send(SpawnExplosionEvent {
-                            x: transform.translation.x,
-                            y: transform.translation.y,
-                        });
-                        game_state.set(GameState::Crashed);
-                    }
-                }
             }
-            info!("Linear velocity Y: {:?}", linear_velocity.y);
         }
+        info!("Linear velocity Y: {:?}", linear_velocity.y);
     }
 }
 
+/// Landscape collider vertices in world coordinates.
+pub fn landscape_world_vertices(landscape: &WorldBoundsVertices2D) -> Vec<Vec2> {
+    landscape
+        .data
+        .iter()
+        .map(|vertex| LANDSCAPE_TRANSLATION + *vertex * LANDSCAPE_SCALE)
+        .collect()
+}
+
+/// Highest landscape point right below `x`, if the landscape spans it.
+pub fn terrain_height_at(vertices: &[Vec2], x: f32) -> Option<f32> {
+    vertices
+        .windows(2)
+        .filter_map(|segment| {
+            let (a, b) = (segment[0], segment[1]);
+            if x < a.x.min(b.x) || x > a.x.max(b.x) {
+                None
+            } else if (b.x - a.x).abs() < f32::EPSILON {
+                Some(a.y.max(b.y))
+            } else {
+                Some(a.y + (b.y - a.y) * (x - a.x) / (b.x - a.x))
+            }
+        })
+        .reduce(f32::max)
+}
+
+/// Highest landscape point between `from_x` and `to_x`.
+pub fn max_terrain_height_between(vertices: &[Vec2], from_x: f32, to_x: f32) -> Option<f32> {
+    let (min_x, max_x) = (from_x.min(to_x), from_x.max(to_x));
+    vertices
+        .iter()
+        .filter(|vertex| vertex.x >= min_x && vertex.x <= max_x)
+        .map(|vertex| vertex.y)
+        .chain(terrain_height_at(vertices, min_x))
+        .chain(terrain_height_at(vertices, max_x))
+        .reduce(f32::max)
+}
+
 // Components
 #[derive(Component, Clone, Debug)]
 pub struct Platform {
     pub factor: i32,
+    pub width: f32,
 }
+
+#[derive(Component)]
+pub struct WorldBounds;
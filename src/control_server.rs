@@ -0,0 +1,413 @@
+use avian2d::prelude::{GravityScale, LinearVelocity};
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::explosion::{CrashCause, SpawnExplosionEvent};
+use crate::game::{InGameSet, Scores, SpaceshipJustLandedEvent};
+use crate::movement::ReadyToLand;
+use crate::spaceship::{Player, PlayerAction};
+use crate::state::{AppState, GameState, LaunchConditions};
+
+/// Environment variable holding the endpoint the control server listens on, like `tcp:127.0.0.1:7878`
+/// or `unix:/tmp/rusty-lander.sock`. The server is disabled when it is not set.
+pub const CONTROL_ENDPOINT_VAR: &str = "RUSTY_LANDER_CONTROL";
+
+pub struct ControlServerPlugin;
+
+impl Plugin for ControlServerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ExternalActions>()
+            .add_systems(Startup, start_control_server_system)
+            .add_systems(
+                PreUpdate,
+                (
+                    handle_control_commands_system,
+                    press_external_actions_system.run_if(in_state(GameState::Landing)),
+                )
+                    .chain()
+                    .in_set(InputManagerSystem::ManualControl)
+                    .run_if(resource_exists::<ControlServer>),
+            )
+            .add_systems(
+                FixedUpdate,
+                stream_lander_state_system
+                    .after(InGameSet::Physics)
+                    .run_if(in_state(AppState::Game))
+                    .run_if(resource_exists::<ControlServer>),
+            )
+            .add_systems(
+                Update,
+                stream_landing_outcomes_system
+                    .run_if(in_state(AppState::Game))
+                    .run_if(resource_exists::<ControlServer>),
+            );
+    }
+}
+
+// Systems
+fn start_control_server_system(mut commands: Commands) {
+    let Ok(endpoint) = std::env::var(CONTROL_ENDPOINT_VAR) else {
+        return;
+    };
+    let server = endpoint
+        .parse::<ControlEndpoint>()
+        .and_then(|endpoint| ControlServer::bind(&endpoint));
+    match server {
+        Ok(server) => {
+            info!("Control server listening on {}", endpoint);
+            commands.insert_resource(server);
+        }
+        Err(error) => error!(
+            "Unable to start the control server on {}: {}",
+            endpoint, error
+        ),
+    }
+}
+
+fn handle_control_commands_system(
+    app_state: Res<State<AppState>>,
+    game_state: Option<Res<State<GameState>>>,
+    mut server: ResMut<ControlServer>,
+    mut external_actions: ResMut<ExternalActions>,
+    mut launch_conditions: ResMut<LaunchConditions>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    let game_state = game_state.map(|game_state| *game_state.get());
+    for command in server.poll() {
+        match command {
+            ClientCommand::Actions { actions } => external_actions.0 = actions,
+            ClientCommand::Pause => {
+                if game_state == Some(GameState::Landing) {
+                    next_game_state.set(GameState::Paused);
+                }
+            }
+            ClientCommand::Resume => {
+                if game_state == Some(GameState::Paused) {
+                    next_game_state.set(GameState::Landing);
+                }
+            }
+            ClientCommand::Seed { seed } => *launch_conditions = LaunchConditions::from_seed(seed),
+            ClientCommand::Reset { seed } => {
+                if let Some(seed) = seed {
+                    *launch_conditions = LaunchConditions::from_seed(seed);
+                }
+                external_actions.0.clear();
+                if app_state.get() == &AppState::Game {
+                    next_game_state.set(GameState::Setup);
+                } else {
+                    next_app_state.set(AppState::Game);
+                }
+            }
+        }
+    }
+}
+
+fn press_external_actions_system(
+    external_actions: Res<ExternalActions>,
+    mut action_state_query: Query<&mut ActionState<PlayerAction>, With<Player>>,
+) {
+    let Ok(mut action_state) = action_state_query.get_single_mut() else {
+        return;
+    };
+    for action in external_actions.0.iter() {
+        action_state.press(action);
+    }
+}
+
+fn stream_lander_state_system(
+    game_state: Res<State<GameState>>,
+    scores: Res<Scores>,
+    spaceship_query: Query<
+        (&Transform, &LinearVelocity, &GravityScale, Has<ReadyToLand>),
+        With<Player>,
+    >,
+    mut server: ResMut<ControlServer>,
+    mut tick: Local<u64>,
+) {
+    let Ok((transform, linear_velocity, gravity_scale, ready_to_land)) =
+        spaceship_query.get_single()
+    else {
+        return;
+    };
+    *tick += 1;
+    server.broadcast(&ServerMessage::State(LanderState {
+        tick: *tick,
+        state: *game_state.get(),
+        position: transform.translation.xy(),
+        velocity: linear_velocity.0,
+        fuel: scores.fuel_quantity,
+        gravity_scale: gravity_scale.0,
+        score: scores.score,
+        ready_to_land,
+    }));
+}
+
+fn stream_landing_outcomes_system(
+    mut landed_events: EventReader<SpaceshipJustLandedEvent>,
+    mut explosion_events: EventReader<SpawnExplosionEvent>,
+    mut server: ResMut<ControlServer>,
+) {
+    for event in landed_events.read() {
+        server.broadcast(&ServerMessage::Landed {
+            platform_factor: event.platform.factor,
+            vertical_velocity: event.linear_velocity.y,
+            score: event.score,
+        });
+    }
+    for event in explosion_events.read() {
+        server.broadcast(&ServerMessage::Crashed { cause: event.cause });
+    }
+}
+
+// Protocol
+/// Lines sent by the clients, like `{"command":"actions","actions":["MainThrusterSmall"]}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ClientCommand {
+    /// Thrusters to hold until the next actions command
+    Actions {
+        actions: Vec<PlayerAction>,
+    },
+    Pause,
+    Resume,
+    /// Restarts the landing attempt, from the launch conditions of `seed` when given
+    Reset {
+        #[serde(default)]
+        seed: Option<u64>,
+    },
+    /// Launch conditions used by the next landing attempts
+    Seed {
+        seed: u64,
+    },
+}
+
+/// Lines sent to every connected client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    State(LanderState),
+    Landed {
+        platform_factor: i32,
+        vertical_velocity: f32,
+        score: i32,
+    },
+    Crashed {
+        cause: CrashCause,
+    },
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanderState {
+    /// Fixed ticks streamed so far
+    pub tick: u64,
+    pub state: GameState,
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub fuel: f32,
+    pub gravity_scale: f32,
+    pub score: i32,
+    pub ready_to_land: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlEndpoint {
+    Tcp(SocketAddr),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl FromStr for ControlEndpoint {
+    type Err = io::Error;
+
+    fn from_str(endpoint: &str) -> Result<Self, Self::Err> {
+        #[cfg(unix)]
+        if let Some(path) = endpoint.strip_prefix("unix:") {
+            return Ok(ControlEndpoint::Unix(PathBuf::from(path)));
+        }
+        let address = endpoint.strip_prefix("tcp:").unwrap_or(endpoint);
+        address
+            .parse()
+            .map(ControlEndpoint::Tcp)
+            .map_err(|error| io::Error::new(ErrorKind::InvalidInput, error))
+    }
+}
+
+// Transport
+trait ControlStream: Read + Write + Send + Sync {}
+
+impl ControlStream for TcpStream {}
+
+#[cfg(unix)]
+impl ControlStream for UnixStream {}
+
+enum ControlListener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl ControlListener {
+    fn accept(&self) -> io::Result<Box<dyn ControlStream>> {
+        match self {
+            ControlListener::Tcp(listener) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nonblocking(true)?;
+                stream.set_nodelay(true)?;
+                Ok(Box::new(stream))
+            }
+            #[cfg(unix)]
+            ControlListener::Unix(listener) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nonblocking(true)?;
+                Ok(Box::new(stream))
+            }
+        }
+    }
+}
+
+struct ControlClient {
+    stream: Box<dyn ControlStream>,
+    pending: Vec<u8>,
+    connected: bool,
+}
+
+impl ControlClient {
+    fn read_commands(&mut self) -> Vec<Result<ClientCommand, String>> {
+        let mut chunk = [0; 1024];
+        loop {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => {
+                    self.connected = false;
+                    break;
+                }
+                Ok(read) => self.pending.extend_from_slice(&chunk[..read]),
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => {
+                    self.connected = false;
+                    break;
+                }
+            }
+        }
+        let mut commands = vec![];
+        while let Some(end) = self.pending.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            if line.trim().is_empty() {
+                continue;
+            }
+            commands.push(serde_json::from_str(line.trim()).map_err(|error| error.to_string()));
+        }
+        commands
+    }
+
+    fn send_line(&mut self, line: &[u8]) {
+        // a client too slow to keep up with the stream is dropped instead of stalling the game
+        if self.stream.write_all(line).is_err() {
+            self.connected = false;
+        }
+    }
+}
+
+/// Non blocking JSON lines server, every line is a `ClientCommand` or a `ServerMessage`.
+#[derive(Resource)]
+pub struct ControlServer {
+    listener: ControlListener,
+    clients: Vec<ControlClient>,
+}
+
+impl ControlServer {
+    pub fn bind(endpoint: &ControlEndpoint) -> io::Result<Self> {
+        let listener = match endpoint {
+            ControlEndpoint::Tcp(address) => {
+                let listener = TcpListener::bind(address)?;
+                listener.set_nonblocking(true)?;
+                ControlListener::Tcp(listener)
+            }
+            #[cfg(unix)]
+            ControlEndpoint::Unix(path) => {
+                // a previous run could have left the socket file behind
+                if path.exists() {
+                    std::fs::remove_file(path)?;
+                }
+                let listener = UnixListener::bind(path)?;
+                listener.set_nonblocking(true)?;
+                ControlListener::Unix(listener)
+            }
+        };
+        Ok(Self {
+            listener,
+            clients: vec![],
+        })
+    }
+
+    /// Address actually bound, handy when binding the TCP port 0.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        match &self.listener {
+            ControlListener::Tcp(listener) => listener.local_addr().ok(),
+            #[cfg(unix)]
+            ControlListener::Unix(_) => None,
+        }
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Accepts the pending connections and returns every command received since the last poll.
+    /// Malformed lines are answered with an error message.
+    pub fn poll(&mut self) -> Vec<ClientCommand> {
+        while let Ok(stream) = self.listener.accept() {
+            self.clients.push(ControlClient {
+                stream,
+                pending: vec![],
+                connected: true,
+            });
+        }
+        let mut commands = vec![];
+        for client in self.clients.iter_mut() {
+            for command in client.read_commands() {
+                match command {
+                    Ok(command) => commands.push(command),
+                    Err(message) => {
+                        warn!("Invalid control command: {}", message);
+                        client.send_line(&message_line(&ServerMessage::Error { message }));
+                    }
+                }
+            }
+        }
+        self.clients.retain(|client| client.connected);
+        commands
+    }
+
+    pub fn broadcast(&mut self, message: &ServerMessage) {
+        let line = message_line(message);
+        for client in self.clients.iter_mut() {
+            client.send_line(&line);
+        }
+        self.clients.retain(|client| client.connected);
+    }
+}
+
+fn message_line(message: &ServerMessage) -> Vec<u8> {
+    let mut line = serde_json::to_vec(message).expect("failed to serialize a control message");
+    line.push(b'\n');
+    line
+}
+
+// Resources
+/// Thrusters held by the external bot.
+#[derive(Resource, Default, Debug)]
+pub struct ExternalActions(pub Vec<PlayerAction>);
@@ -0,0 +1,819 @@
+use bevy::input::gamepad::GamepadButton;
+use bevy::prelude::*;
+use bevy_persistent::Persistent;
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::asset_loader::UiAssets;
+use crate::localization::{Localization, LocalizedText};
+use crate::menu::{
+    ButtonId, MenuAction, MenuCustomEvent, MenuEntry, MenuEntryAction, MenuHandler, GREY_COLOR,
+    SECONDARY_COLOR,
+};
+use crate::profile::{ProfileSelectedEvent, Profiles};
+use crate::spaceship::{Player, PlayerAction};
+use crate::state::AppState;
+use crate::storage::{load_persistent, Versioned};
+
+const CONTROLS_ENTRIES: [ControlsEntry; 16] = [
+    ControlsEntry::Player(PlayerAction::LeftThruster),
+    ControlsEntry::Player(PlayerAction::RightThruster),
+    ControlsEntry::Player(PlayerAction::MainThrusterBig),
+    ControlsEntry::Player(PlayerAction::MainThrusterMedium),
+    ControlsEntry::Player(PlayerAction::MainThrusterSmall),
+    ControlsEntry::Menu(MenuAction::MenuUp),
+    ControlsEntry::Menu(MenuAction::MenuDown),
+    ControlsEntry::Menu(MenuAction::Accept),
+    ControlsEntry::Menu(MenuAction::PauseUnpause),
+    ControlsEntry::Menu(MenuAction::ToggleAutopilot),
+    ControlsEntry::Menu(MenuAction::AutopilotTarget),
+    ControlsEntry::Menu(MenuAction::ToggleTrajectory),
+    ControlsEntry::Menu(MenuAction::ToggleFullscreen),
+    ControlsEntry::PointerControls,
+    ControlsEntry::ResetDefaults,
+    ControlsEntry::Back,
+];
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RebindingState>()
+            .add_systems(Startup, persist_key_bindings_system)
+            .add_systems(
+                PostUpdate,
+                persist_key_bindings_system.run_if(on_event::<ProfileSelectedEvent>()),
+            )
+            .add_systems(OnEnter(AppState::Controls), spawn_controls_menu_system)
+            .add_systems(
+                Update,
+                (
+                    apply_key_bindings_system
+                        .run_if(resource_exists_and_changed::<Persistent<KeyBindings>>),
+                    (
+                        capture_rebinding_input_system,
+                        controls_menu_input_system,
+                        update_controls_menu_texts_system,
+                    )
+                        .chain()
+                        .run_if(in_state(AppState::Controls)),
+                ),
+            );
+    }
+}
+
+// Conditions
+pub fn not_rebinding(rebinding: Res<RebindingState>) -> bool {
+    rebinding.awaiting.is_none()
+}
+
+// Systems
+fn persist_key_bindings_system(mut commands: Commands, profiles: Res<Persistent<Profiles>>) {
+    let mut key_bindings =
+        load_persistent::<KeyBindings>("key bindings", &profiles.active_dir(), "bindings.json");
+    let mut complete_key_bindings = KeyBindings::clone(&key_bindings);
+    if complete_key_bindings.add_missing_actions() {
+        if let Err(error) = key_bindings.set(complete_key_bindings) {
+            error!("failed to add missing key bindings: {}", error);
+        }
+    }
+    commands.insert_resource(key_bindings)
+}
+
+fn apply_key_bindings_system(
+    key_bindings: Res<Persistent<KeyBindings>>,
+    mut commands: Commands,
+    mut player_input_map_query: Query<&mut InputMap<PlayerAction>, With<Player>>,
+) {
+    commands.insert_resource(key_bindings.menu_input_map());
+    for mut input_map in player_input_map_query.iter_mut() {
+        *input_map = key_bindings.player_input_map();
+    }
+}
+
+fn spawn_controls_menu_system(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    key_bindings: Res<Persistent<KeyBindings>>,
+    localization: Res<Localization>,
+    mut rebinding: ResMut<RebindingState>,
+) {
+    rebinding.awaiting = None;
+    rebinding.feedback = localization.text("controls.rebind_hint").into();
+    let labels: Vec<String> = CONTROLS_ENTRIES
+        .iter()
+        .map(|entry| entry.label(&key_bindings, &localization))
+        .collect();
+    commands
+        .spawn((
+            StateScoped(AppState::Controls),
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            MenuHandler {
+                main_text: localization.text("controls.title").into(),
+                main_text_color: SECONDARY_COLOR,
+                main_text_blink: false,
+                entries: CONTROLS_ENTRIES
+                    .iter()
+                    .zip(labels.iter())
+                    .map(|(entry, label)| {
+                        let action = match entry {
+                            ControlsEntry::Back => MenuEntryAction::Back,
+                            entry => MenuEntryAction::Custom(entry.key()),
+                        };
+                        MenuEntry::new(label.clone(), action)
+                    })
+                    .collect(),
+                selected_id: 0,
+                escape_action: Some(MenuEntryAction::Back),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                LocalizedText("controls.title"),
+                TextBundle::from_section(
+                    localization.text("controls.title"),
+                    TextStyle {
+                        font: assets.font_kenvector.clone(),
+                        font_size: 60.0,
+                        color: SECONDARY_COLOR,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                }),
+            ));
+            for (i, label) in labels.iter().enumerate() {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(640.0),
+                                min_height: Val::Px(30.0),
+                                border: UiRect::all(Val::Px(3.0)),
+                                padding: UiRect::horizontal(Val::Px(12.0)),
+                                align_items: AlignItems::Center,
+                                margin: UiRect::all(Val::Px(2.0)),
+                                ..default()
+                            },
+                            border_radius: BorderRadius::all(Val::Px(6.0)),
+                            ..default()
+                        },
+                        ButtonId(i as i32),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            ControlsEntryText(i),
+                            TextBundle::from_section(
+                                label.clone(),
+                                TextStyle {
+                                    font: assets.font_vt323.clone(),
+                                    font_size: 25.0,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                        ));
+                    });
+            }
+            parent.spawn((
+                ControlsFeedbackText,
+                TextBundle::from_section(
+                    rebinding.feedback.clone(),
+                    TextStyle {
+                        font: assets.font_vt323.clone(),
+                        font_size: 22.0,
+                        color: GREY_COLOR,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::top(Val::Px(15.0)),
+                    ..default()
+                }),
+            ));
+        });
+}
+
+fn capture_rebinding_input_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    localization: Res<Localization>,
+    mut menu_action_state: ResMut<ActionState<MenuAction>>,
+    mut rebinding: ResMut<RebindingState>,
+    mut key_bindings: ResMut<Persistent<KeyBindings>>,
+) {
+    let Some(entry) = rebinding.awaiting else {
+        return;
+    };
+    if keys.just_pressed(KeyCode::Backspace) {
+        rebinding.awaiting = None;
+        rebinding.feedback = localization.text("controls.rebind_cancelled").into();
+        return;
+    }
+    // Escape is reserved to leave the game, so it can't be bound to any action
+    let binding = keys
+        .get_just_pressed()
+        .find(|key_code| **key_code != KeyCode::Escape)
+        .map(|key_code| Binding::Key(*key_code))
+        .or_else(|| {
+            gamepad_buttons
+                .get_just_pressed()
+                .next()
+                .map(|gamepad_button| Binding::Gamepad(gamepad_button.button_type))
+        });
+    let Some(binding) = binding else {
+        return;
+    };
+    rebinding.awaiting = None;
+    // the captured key could also be the accept key, it must not accept the menu entry again
+    menu_action_state.consume(&MenuAction::Accept);
+    let mut new_key_bindings = KeyBindings::clone(&key_bindings);
+    let result = match entry {
+        ControlsEntry::Player(action) => new_key_bindings.rebind_player(action, binding),
+        ControlsEntry::Menu(action) => new_key_bindings.rebind_menu(action, binding),
+        _ => return,
+    };
+    match result {
+        Ok(()) => {
+            if let Err(error) = key_bindings.set(new_key_bindings) {
+                error!("failed to update key bindings: {}", error);
+            }
+            rebinding.feedback = localization.format(
+                "controls.bound",
+                &[localization.text(entry.key()), &binding.label()],
+            );
+        }
+        Err(conflicting_action) => {
+            rebinding.feedback = localization.format(
+                "controls.conflict",
+                &[
+                    &binding.label(),
+                    localization.text(conflicting_action.key()),
+                ],
+            );
+        }
+    }
+}
+
+fn controls_menu_input_system(
+    localization: Res<Localization>,
+    mut custom_events: EventReader<MenuCustomEvent>,
+    mut rebinding: ResMut<RebindingState>,
+    mut key_bindings: ResMut<Persistent<KeyBindings>>,
+) {
+    let Some(entry) = custom_events
+        .read()
+        .find_map(|MenuCustomEvent(id)| CONTROLS_ENTRIES.iter().find(|entry| entry.key() == *id))
+    else {
+        return;
+    };
+    match *entry {
+        ControlsEntry::ResetDefaults => {
+            if let Err(error) = key_bindings.set(KeyBindings::default()) {
+                error!("failed to reset key bindings: {}", error);
+            }
+            rebinding.feedback = localization.text("controls.defaults_restored").into();
+        }
+        ControlsEntry::PointerControls => {
+            if let Err(error) = key_bindings.update(|key_bindings| {
+                key_bindings.pointer_controls = !key_bindings.pointer_controls;
+            }) {
+                error!("failed to update pointer controls: {}", error);
+            }
+        }
+        ControlsEntry::Back => {}
+        entry => {
+            rebinding.awaiting = Some(entry);
+            rebinding.feedback = localization.text("controls.rebind_waiting").into();
+        }
+    }
+}
+
+fn update_controls_menu_texts_system(
+    key_bindings: Res<Persistent<KeyBindings>>,
+    localization: Res<Localization>,
+    rebinding: Res<RebindingState>,
+    mut entry_texts_query: Query<(&ControlsEntryText, &mut Text), Without<ControlsFeedbackText>>,
+    mut feedback_text_query: Query<&mut Text, With<ControlsFeedbackText>>,
+) {
+    if !key_bindings.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+    for (entry_text, mut text) in entry_texts_query.iter_mut() {
+        let entry = CONTROLS_ENTRIES[entry_text.0];
+        text.sections[0].value = if rebinding.awaiting == Some(entry) {
+            format!("{:<18}...", localization.text(entry.key()))
+        } else {
+            entry.label(&key_bindings, &localization)
+        };
+    }
+    if let Ok(mut text) = feedback_text_query.get_single_mut() {
+        text.sections[0].value = rebinding.feedback.clone();
+    }
+}
+
+/// Localization key of the action name, its description key adds the `.description` suffix.
+fn player_action_key(action: PlayerAction) -> &'static str {
+    match action {
+        PlayerAction::Idle => "action.idle",
+        PlayerAction::LeftThruster => "action.left_thruster",
+        PlayerAction::RightThruster => "action.right_thruster",
+        PlayerAction::MainThrusterBig => "action.big_thrust",
+        PlayerAction::MainThrusterMedium => "action.medium_thrust",
+        PlayerAction::MainThrusterSmall => "action.small_thrust",
+    }
+}
+
+fn menu_action_key(action: MenuAction) -> &'static str {
+    match action {
+        MenuAction::MenuUp => "menu_action.menu_up",
+        MenuAction::MenuDown => "menu_action.menu_down",
+        MenuAction::Accept => "menu_action.accept",
+        MenuAction::PauseUnpause => "menu_action.pause",
+        MenuAction::ToggleAutopilot => "menu_action.autopilot",
+        MenuAction::AutopilotTarget => "menu_action.autopilot_pad",
+        MenuAction::ToggleTrajectory => "menu_action.trajectory",
+        MenuAction::ToggleFullscreen => "menu_action.fullscreen",
+    }
+}
+
+/// Menu actions also handled during a flight, their bindings can't be shared with the thrusters.
+/// The menu navigation ones are only read in menus, so they share the thruster keys by default.
+fn is_flight_menu_action(action: MenuAction) -> bool {
+    !matches!(
+        action,
+        MenuAction::MenuUp | MenuAction::MenuDown | MenuAction::Accept
+    )
+}
+
+/// First action in `entries` using `binding`, leaving `except` aside.
+fn action_using<A: Copy + PartialEq>(
+    entries: &[ActionBindings<A>],
+    binding: Binding,
+    except: Option<A>,
+) -> Option<A> {
+    entries
+        .iter()
+        .find(|entry| Some(entry.action) != except && entry.bindings.contains(&binding))
+        .map(|entry| entry.action)
+}
+
+/// Replaces the `action` bindings of the same device kind than `binding`.
+/// Returns the action that already uses `binding` when there is a conflict.
+fn rebind<A: Copy + PartialEq>(
+    entries: &mut Vec<ActionBindings<A>>,
+    action: A,
+    binding: Binding,
+) -> Result<(), A> {
+    if let Some(conflict) = action_using(entries, binding, Some(action)) {
+        return Err(conflict);
+    }
+    match entries.iter_mut().find(|entry| entry.action == action) {
+        Some(entry) => {
+            entry
+                .bindings
+                .retain(|current| !current.is_same_device(&binding));
+            entry.bindings.push(binding);
+        }
+        None => entries.push(ActionBindings::new(action, [binding])),
+    }
+    Ok(())
+}
+
+fn build_input_map<A: Actionlike + Copy>(entries: &[ActionBindings<A>]) -> InputMap<A> {
+    let mut input_map = InputMap::default();
+    for entry in entries {
+        for binding in &entry.bindings {
+            match *binding {
+                Binding::Key(key_code) => {
+                    input_map.insert(entry.action, key_code);
+                }
+                Binding::Gamepad(button_type) => {
+                    input_map.insert(entry.action, button_type);
+                }
+            }
+        }
+    }
+    input_map
+}
+
+fn bindings_label<A: PartialEq>(
+    entries: &[ActionBindings<A>],
+    action: A,
+    separator: &str,
+    unbound: &str,
+) -> String {
+    let labels: Vec<String> = entries
+        .iter()
+        .filter(|entry| entry.action == action)
+        .flat_map(|entry| entry.bindings.iter().map(Binding::label))
+        .collect();
+    if labels.is_empty() {
+        unbound.into()
+    } else {
+        labels.join(separator)
+    }
+}
+
+// Resources
+#[derive(Resource, Default)]
+pub struct RebindingState {
+    awaiting: Option<ControlsEntry>,
+    feedback: String,
+}
+
+#[derive(Resource, Serialize, Deserialize, Debug, Clone)]
+pub struct KeyBindings {
+    #[serde(default)]
+    pub version: u32,
+    pub player: Vec<ActionBindings<PlayerAction>>,
+    pub menu: Vec<ActionBindings<MenuAction>>,
+    #[serde(default)]
+    pub pointer_controls: bool,
+}
+
+impl Versioned for KeyBindings {
+    const VERSION: u32 = 1;
+}
+
+impl KeyBindings {
+    pub fn player_input_map(&self) -> InputMap<PlayerAction> {
+        build_input_map(&self.player)
+    }
+
+    pub fn menu_input_map(&self) -> InputMap<MenuAction> {
+        build_input_map(&self.menu)
+    }
+
+    /// Binds `binding` to a thruster, unless a thruster or a menu action used during a flight
+    /// already uses it.
+    pub fn rebind_player(
+        &mut self,
+        action: PlayerAction,
+        binding: Binding,
+    ) -> Result<(), BoundAction> {
+        // menu bindings are unique, so the only menu action using it is checked
+        if let Some(conflict) = action_using(&self.menu, binding, None)
+            .filter(|conflict| is_flight_menu_action(*conflict))
+        {
+            return Err(BoundAction::Menu(conflict));
+        }
+        rebind(&mut self.player, action, binding).map_err(BoundAction::Player)
+    }
+
+    /// Binds `binding` to a menu action, unless another menu action already uses it, or a thruster
+    /// when the action is also used during a flight.
+    pub fn rebind_menu(&mut self, action: MenuAction, binding: Binding) -> Result<(), BoundAction> {
+        if is_flight_menu_action(action) {
+            if let Some(conflict) = action_using(&self.player, binding, None) {
+                return Err(BoundAction::Player(conflict));
+            }
+        }
+        rebind(&mut self.menu, action, binding).map_err(BoundAction::Menu)
+    }
+
+    pub fn player_bindings_label(
+        &self,
+        action: PlayerAction,
+        localization: &Localization,
+    ) -> String {
+        bindings_label(
+            &self.player,
+            action,
+            " / ",
+            localization.text("controls.unbound"),
+        )
+    }
+
+    pub fn menu_bindings_label(&self, action: MenuAction, localization: &Localization) -> String {
+        bindings_label(
+            &self.menu,
+            action,
+            " / ",
+            localization.text("controls.unbound"),
+        )
+    }
+
+    /// Adds the default bindings of the actions that weren't available when the bindings were persisted.
+    pub fn add_missing_actions(&mut self) -> bool {
+        let defaults = KeyBindings::default();
+        let mut added = false;
+        for entry in defaults.player {
+            if !self
+                .player
+                .iter()
+                .any(|current| current.action == entry.action)
+            {
+                self.player.push(entry);
+                added = true;
+            }
+        }
+        for entry in defaults.menu {
+            if !self
+                .menu
+                .iter()
+                .any(|current| current.action == entry.action)
+            {
+                self.menu.push(entry);
+                added = true;
+            }
+        }
+        added
+    }
+
+    /// Whether any binding of `action` has been pressed this frame, even if it has already been released.
+    pub fn player_action_just_pressed(
+        &self,
+        action: PlayerAction,
+        keys: &ButtonInput<KeyCode>,
+        gamepad_buttons: &ButtonInput<GamepadButton>,
+    ) -> bool {
+        self.player
+            .iter()
+            .filter(|entry| entry.action == action)
+            .flat_map(|entry| entry.bindings.iter())
+            .any(|binding| match *binding {
+                Binding::Key(key_code) => keys.just_pressed(key_code),
+                Binding::Gamepad(button_type) => gamepad_buttons
+                    .get_just_pressed()
+                    .any(|gamepad_button| gamepad_button.button_type == button_type),
+            })
+    }
+
+    pub fn instructions_text(&self, localization: &Localization) -> String {
+        self.player
+            .iter()
+            .map(|entry| {
+                localization.format(
+                    "instructions.press",
+                    &[
+                        &bindings_label(
+                            &self.player,
+                            entry.action,
+                            localization.text("instructions.or"),
+                            localization.text("controls.unbound"),
+                        ),
+                        localization
+                            .text(&format!("{}.description", player_action_key(entry.action))),
+                    ],
+                )
+            })
+            .chain(
+                self.pointer_controls
+                    .then(|| localization.text("instructions.pointer").to_string()),
+            )
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            version: Self::VERSION,
+            player: vec![
+                ActionBindings::new(
+                    PlayerAction::LeftThruster,
+                    [
+                        Binding::Key(KeyCode::KeyA),
+                        Binding::Key(KeyCode::ArrowLeft),
+                    ],
+                ),
+                ActionBindings::new(
+                    PlayerAction::RightThruster,
+                    [
+                        Binding::Key(KeyCode::KeyD),
+                        Binding::Key(KeyCode::ArrowRight),
+                    ],
+                ),
+                ActionBindings::new(
+                    PlayerAction::MainThrusterBig,
+                    [Binding::Key(KeyCode::Digit2), Binding::Key(KeyCode::Space)],
+                ),
+                ActionBindings::new(
+                    PlayerAction::MainThrusterMedium,
+                    [Binding::Key(KeyCode::KeyW), Binding::Key(KeyCode::ArrowUp)],
+                ),
+                ActionBindings::new(
+                    PlayerAction::MainThrusterSmall,
+                    [
+                        Binding::Key(KeyCode::KeyS),
+                        Binding::Key(KeyCode::ArrowDown),
+                    ],
+                ),
+            ],
+            menu: vec![
+                ActionBindings::new(
+                    MenuAction::MenuUp,
+                    [Binding::Key(KeyCode::KeyW), Binding::Key(KeyCode::ArrowUp)],
+                ),
+                ActionBindings::new(
+                    MenuAction::MenuDown,
+                    [
+                        Binding::Key(KeyCode::KeyS),
+                        Binding::Key(KeyCode::ArrowDown),
+                    ],
+                ),
+                ActionBindings::new(
+                    MenuAction::Accept,
+                    [
+                        Binding::Key(KeyCode::Enter),
+                        Binding::Gamepad(GamepadButtonType::South),
+                    ],
+                ),
+                ActionBindings::new(
+                    MenuAction::PauseUnpause,
+                    [
+                        Binding::Key(KeyCode::KeyP),
+                        Binding::Gamepad(GamepadButtonType::Start),
+                    ],
+                ),
+                ActionBindings::new(MenuAction::ToggleAutopilot, [Binding::Key(KeyCode::KeyT)]),
+                ActionBindings::new(MenuAction::AutopilotTarget, [Binding::Key(KeyCode::KeyY)]),
+                ActionBindings::new(MenuAction::ToggleTrajectory, [Binding::Key(KeyCode::KeyG)]),
+                ActionBindings::new(MenuAction::ToggleFullscreen, [Binding::Key(KeyCode::F11)]),
+            ],
+            pointer_controls: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActionBindings<A> {
+    pub action: A,
+    pub bindings: Vec<Binding>,
+}
+
+impl<A> ActionBindings<A> {
+    pub fn new(action: A, bindings: impl IntoIterator<Item = Binding>) -> Self {
+        Self {
+            action,
+            bindings: bindings.into_iter().collect(),
+        }
+    }
+}
+
+/// Action of either input map, the one already using a binding when rebinding fails.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BoundAction {
+    Player(PlayerAction),
+    Menu(MenuAction),
+}
+
+impl BoundAction {
+    /// Localization key of the action name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            BoundAction::Player(action) => player_action_key(*action),
+            BoundAction::Menu(action) => menu_action_key(*action),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(KeyCode),
+    Gamepad(GamepadButtonType),
+}
+
+impl Binding {
+    pub fn label(&self) -> String {
+        match self {
+            Binding::Key(key_code) => {
+                let name = format!("{:?}", key_code);
+                for prefix in ["Key", "Digit", "Arrow"] {
+                    if let Some(stripped) = name.strip_prefix(prefix) {
+                        return stripped.to_uppercase();
+                    }
+                }
+                name.to_uppercase()
+            }
+            Binding::Gamepad(button_type) => format!("PAD {:?}", button_type).to_uppercase(),
+        }
+    }
+
+    fn is_same_device(&self, other: &Binding) -> bool {
+        matches!(
+            (self, other),
+            (Binding::Key(_), Binding::Key(_)) | (Binding::Gamepad(_), Binding::Gamepad(_))
+        )
+    }
+}
+
+// Controls menu entries
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ControlsEntry {
+    Player(PlayerAction),
+    Menu(MenuAction),
+    PointerControls,
+    ResetDefaults,
+    Back,
+}
+
+impl ControlsEntry {
+    /// Localization key of the entry name.
+    fn key(&self) -> &'static str {
+        match self {
+            ControlsEntry::Player(action) => player_action_key(*action),
+            ControlsEntry::Menu(action) => menu_action_key(*action),
+            ControlsEntry::PointerControls => "controls.pointer_controls",
+            ControlsEntry::ResetDefaults => "controls.reset_defaults",
+            ControlsEntry::Back => "menu.menu",
+        }
+    }
+
+    fn label(&self, key_bindings: &KeyBindings, localization: &Localization) -> String {
+        let name = localization.text(self.key());
+        match self {
+            ControlsEntry::Player(action) => format!(
+                "{:<18}{}",
+                name,
+                key_bindings.player_bindings_label(*action, localization)
+            ),
+            ControlsEntry::Menu(action) => format!(
+                "{:<18}{}",
+                name,
+                key_bindings.menu_bindings_label(*action, localization)
+            ),
+            ControlsEntry::PointerControls => format!(
+                "{:<18}{}",
+                name,
+                localization.text(if key_bindings.pointer_controls {
+                    "common.on"
+                } else {
+                    "common.off"
+                })
+            ),
+            _ => name.into(),
+        }
+    }
+}
+
+// Components
+#[derive(Component)]
+struct ControlsEntryText(usize);
+
+#[derive(Component)]
+struct ControlsFeedbackText;
+
+#[test]
+fn rebind_test() {
+    let mut key_bindings = KeyBindings::default();
+    assert_eq!(
+        key_bindings.rebind_player(PlayerAction::MainThrusterBig, Binding::Key(KeyCode::KeyB)),
+        Ok(())
+    );
+    let big_bindings = &key_bindings.player[2];
+    assert_eq!(big_bindings.action, PlayerAction::MainThrusterBig);
+    assert_eq!(big_bindings.bindings, vec![Binding::Key(KeyCode::KeyB)]);
+    // same map conflict
+    assert_eq!(
+        key_bindings.rebind_player(PlayerAction::LeftThruster, Binding::Key(KeyCode::KeyW)),
+        Err(BoundAction::Player(PlayerAction::MainThrusterMedium))
+    );
+    assert_eq!(
+        key_bindings.rebind_menu(MenuAction::AutopilotTarget, Binding::Key(KeyCode::KeyT)),
+        Err(BoundAction::Menu(MenuAction::ToggleAutopilot))
+    );
+    // cross map conflicts, only with the menu actions used during a flight
+    assert_eq!(
+        key_bindings.rebind_player(PlayerAction::MainThrusterBig, Binding::Key(KeyCode::KeyP)),
+        Err(BoundAction::Menu(MenuAction::PauseUnpause))
+    );
+    assert_eq!(
+        key_bindings.rebind_menu(MenuAction::ToggleAutopilot, Binding::Key(KeyCode::KeyA)),
+        Err(BoundAction::Player(PlayerAction::LeftThruster))
+    );
+    assert_eq!(
+        key_bindings.rebind_menu(MenuAction::MenuUp, Binding::Key(KeyCode::KeyB)),
+        Ok(())
+    );
+}
+
+#[test]
+fn add_missing_actions_test() {
+    // persisted before the assists and the fullscreen existed
+    let json = serde_json::json!({
+        "player": [{ "action": "LeftThruster", "bindings": [{ "Key": "KeyJ" }] }],
+        "menu": [{ "action": "PauseUnpause", "bindings": [{ "Key": "KeyP" }] }]
+    });
+    let mut key_bindings: KeyBindings = serde_json::from_value(json).unwrap();
+    assert!(key_bindings.add_missing_actions());
+    assert_eq!(key_bindings.player.len(), 5);
+    assert_eq!(key_bindings.menu.len(), 8);
+    assert_eq!(
+        key_bindings.player[0].bindings,
+        vec![Binding::Key(KeyCode::KeyJ)]
+    );
+    assert!(!key_bindings.add_missing_actions());
+}
@@ -0,0 +1,252 @@
+use bevy::prelude::*;
+use bevy::render::camera::Viewport;
+use bevy::window::{PresentMode, PrimaryWindow, WindowMode, WindowResized};
+use bevy_persistent::Persistent;
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::attract::attract_mode_active;
+use crate::camera::{ControllersCamera, GameCamera};
+use crate::menu::{no_text_input, MenuAction};
+use crate::settings::Settings;
+use crate::storage::backup_path;
+use crate::{WINDOW_HEIGHT, WINDOW_WIDTH};
+
+/// Seconds without resize events before the new window size is written to disk.
+const RESIZE_SETTLE_SECONDS: f32 = 0.5;
+/// Window settings were saved on their own before the settings menu existed.
+pub const LEGACY_DISPLAY_FILE_NAME: &str = "display.json";
+
+pub struct DisplayPlugin;
+
+impl Plugin for DisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, apply_display_settings_system)
+            .add_systems(
+                Update,
+                (
+                    toggle_fullscreen_system
+                        .run_if(not(attract_mode_active))
+                        .run_if(no_text_input),
+                    remember_window_size_system,
+                    apply_display_settings_system.run_if(resource_changed::<Persistent<Settings>>),
+                    letterbox_cameras_system,
+                )
+                    .chain(),
+            );
+    }
+}
+
+// Systems
+fn apply_display_settings_system(
+    settings: Res<Persistent<Settings>>,
+    mut windows_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = windows_query.get_single_mut() else {
+        return;
+    };
+    let display_settings = &settings.display;
+    let mode = if display_settings.fullscreen {
+        WindowMode::BorderlessFullscreen
+    } else {
+        WindowMode::Windowed
+    };
+    if window.mode != mode {
+        window.mode = mode;
+    }
+    let present_mode = if display_settings.vsync {
+        PresentMode::AutoVsync
+    } else {
+        PresentMode::AutoNoVsync
+    };
+    if window.present_mode != present_mode {
+        window.present_mode = present_mode;
+    }
+    if !display_settings.fullscreen
+        && (window.width() != display_settings.width || window.height() != display_settings.height)
+    {
+        window
+            .resolution
+            .set(display_settings.width, display_settings.height);
+    }
+}
+
+fn toggle_fullscreen_system(
+    menu_action_state: Res<ActionState<MenuAction>>,
+    mut settings: ResMut<Persistent<Settings>>,
+) {
+    if menu_action_state.just_pressed(&MenuAction::ToggleFullscreen) {
+        if let Err(error) =
+            settings.update(|settings| settings.display.fullscreen = !settings.display.fullscreen)
+        {
+            error!("failed to update display settings: {}", error);
+        }
+        info!("Fullscreen: {:?}", settings.display.fullscreen);
+    }
+}
+
+/// Keeps the last windowed size, once the user stops dragging the window borders.
+fn remember_window_size_system(
+    time: Res<Time>,
+    mut resized_events: EventReader<WindowResized>,
+    windows_query: Query<&Window, With<PrimaryWindow>>,
+    mut settings: ResMut<Persistent<Settings>>,
+    mut settle_timer: Local<Option<Timer>>,
+) {
+    if resized_events.read().last().is_some() {
+        *settle_timer = Some(Timer::from_seconds(RESIZE_SETTLE_SECONDS, TimerMode::Once));
+    }
+    let Some(timer) = settle_timer.as_mut() else {
+        return;
+    };
+    if !timer.tick(time.delta()).finished() {
+        return;
+    }
+    *settle_timer = None;
+    let Ok(window) = windows_query.get_single() else {
+        return;
+    };
+    if window.mode != WindowMode::Windowed
+        || (window.width() == settings.display.width && window.height() == settings.display.height)
+    {
+        return;
+    }
+    if let Err(error) = settings.update(|settings| {
+        settings.display.width = window.width();
+        settings.display.height = window.height();
+    }) {
+        error!("failed to update display settings: {}", error);
+    }
+}
+
+/// Both cameras render the play area inside the largest viewport with its aspect ratio, and the
+/// UI is scaled along so the HUD stays anchored to the black bars.
+fn letterbox_cameras_system(
+    windows_query: Query<&Window, With<PrimaryWindow>>,
+    mut cameras_query: Query<&mut Camera, Or<(With<GameCamera>, With<ControllersCamera>)>>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Ok(window) = windows_query.get_single() else {
+        return;
+    };
+    let Some(viewport) = letterbox_viewport(UVec2::new(
+        window.physical_width(),
+        window.physical_height(),
+    )) else {
+        return;
+    };
+    for mut camera in cameras_query.iter_mut() {
+        let current = camera
+            .viewport
+            .as_ref()
+            .map(|current| (current.physical_position, current.physical_size));
+        if current != Some((viewport.physical_position, viewport.physical_size)) {
+            camera.viewport = Some(viewport.clone());
+        }
+    }
+    let scale = viewport.physical_size.y as f32 / window.scale_factor() / WINDOW_HEIGHT;
+    if ui_scale.0 != scale {
+        ui_scale.0 = scale;
+    }
+}
+
+/// Largest viewport with the play area aspect ratio centered in a window of `window_size`
+/// physical pixels. None while the window is minimized.
+pub fn letterbox_viewport(window_size: UVec2) -> Option<Viewport> {
+    if window_size.x == 0 || window_size.y == 0 {
+        return None;
+    }
+    let scale = (window_size.x as f32 / WINDOW_WIDTH).min(window_size.y as f32 / WINDOW_HEIGHT);
+    let physical_size = UVec2::new(
+        ((WINDOW_WIDTH * scale) as u32).clamp(1, window_size.x),
+        ((WINDOW_HEIGHT * scale) as u32).clamp(1, window_size.y),
+    );
+    Some(Viewport {
+        physical_position: (window_size - physical_size) / 2,
+        physical_size,
+        ..default()
+    })
+}
+
+/// Folds the window settings of an old `display.json` in `dir` into the settings, once: the old
+/// file is kept with a `.bak` extension afterwards.
+pub fn import_legacy_display_settings(settings: &mut Persistent<Settings>, dir: &Path) {
+    let path = dir.join(LEGACY_DISPLAY_FILE_NAME);
+    let Ok(content) = fs::read_to_string(&path) else {
+        return;
+    };
+    match serde_json::from_str::<DisplaySettings>(&content) {
+        Ok(display) => {
+            if let Err(error) = settings.update(|settings| settings.display = display.clone()) {
+                error!("failed to import {}: {}", path.display(), error);
+                return;
+            }
+            info!("display settings imported from {}", path.display());
+        }
+        Err(error) => warn!("ignoring unreadable {}: {}", path.display(), error),
+    }
+    if let Err(error) = fs::rename(&path, backup_path(&path, "bak")) {
+        error!("failed to back up {}: {}", path.display(), error);
+    }
+}
+
+/// Window part of the persisted settings.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct DisplaySettings {
+    pub fullscreen: bool,
+    /// Logical size of the window while not in fullscreen
+    pub width: f32,
+    pub height: f32,
+    pub vsync: bool,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            fullscreen: false,
+            width: WINDOW_WIDTH,
+            height: WINDOW_HEIGHT,
+            vsync: true,
+        }
+    }
+}
+
+#[test]
+fn letterbox_viewport_test() {
+    let viewport = letterbox_viewport(UVec2::new(1024, 720)).unwrap();
+    assert_eq!(viewport.physical_position, UVec2::ZERO);
+    assert_eq!(viewport.physical_size, UVec2::new(1024, 720));
+    // wider window, pillarbox on both sides
+    let viewport = letterbox_viewport(UVec2::new(2048, 720)).unwrap();
+    assert_eq!(viewport.physical_position, UVec2::new(512, 0));
+    assert_eq!(viewport.physical_size, UVec2::new(1024, 720));
+    // taller window, letterbox above and below
+    let viewport = letterbox_viewport(UVec2::new(512, 720)).unwrap();
+    assert_eq!(viewport.physical_size, UVec2::new(512, 360));
+    assert_eq!(viewport.physical_position, UVec2::new(0, 180));
+    assert!(letterbox_viewport(UVec2::new(0, 0)).is_none());
+}
+
+#[test]
+fn import_legacy_display_settings_test() {
+    let dir = std::env::temp_dir().join(format!("rusty-lander-display-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(LEGACY_DISPLAY_FILE_NAME);
+    fs::write(
+        &path,
+        r#"{"fullscreen":true,"width":1280.0,"height":900.0}"#,
+    )
+    .unwrap();
+    let mut settings =
+        crate::storage::load_persistent::<Settings>("settings", &dir, "settings.json");
+    import_legacy_display_settings(&mut settings, &dir);
+    assert!(settings.display.fullscreen);
+    assert_eq!(settings.display.width, 1280.0);
+    assert!(settings.display.vsync);
+    assert!(!path.exists());
+    assert!(backup_path(&path, "bak").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
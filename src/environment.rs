@@ -0,0 +1,355 @@
+use avian2d::{math::*, prelude::*};
+use bevy::ecs::event::ManualEventReader;
+use bevy::ecs::world::CommandQueue;
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::texture::{CompressedImageFormats, ImageSampler, ImageType};
+use bevy::time::TimeUpdateStrategy;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::collider::{
+    landscape_world_vertices, spawn_landscape_colliders, terrain_height_at, Platform,
+    WORLD_BOUNDS_MIN_Y,
+};
+use crate::explosion::{CrashCause, SpawnExplosionEvent};
+use crate::game::{
+    landing_score, Scores, SpaceshipJustLandedEvent, WorldBoundsVertices2D, FUEL_QUANTITY,
+};
+use crate::input_buffer::ThrusterTickInput;
+use crate::movement::{LanderPhysicsPlugin, ReadyToLand};
+use crate::spaceship::{lander_collider, lander_physics_bundle, PlayerAction};
+use crate::state::{AppState, GameState, LaunchConditions};
+use crate::{get_world_bounds_resource_2d, GRAVITY, PHYSICS_LENGTH_UNIT};
+
+pub const TICKS_PER_SECOND: f64 = 64.0;
+/// Horizontal offsets where the terrain below the lander is sampled for every observation.
+pub const TERRAIN_SAMPLE_OFFSETS: [f32; 9] = [
+    -200.0, -150.0, -100.0, -50.0, 0.0, 50.0, 100.0, 150.0, 200.0,
+];
+/// Reward lost on a crash.
+const CRASH_PENALTY: f32 = 100.0;
+
+/// Headless, rendering free lander simulation stepped one fixed physics tick at a time,
+/// following the usual reset / step reinforcement learning loop.
+pub struct LanderEnvironment {
+    app: App,
+    config: EnvironmentConfig,
+    lander: Entity,
+    terrain: Vec<Vec2>,
+    steps: u32,
+    done: bool,
+    landed_events: ManualEventReader<SpaceshipJustLandedEvent>,
+    explosion_events: ManualEventReader<SpawnExplosionEvent>,
+}
+
+impl LanderEnvironment {
+    pub fn new(config: EnvironmentConfig) -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            HierarchyPlugin,
+            bevy::state::app::StatesPlugin,
+            AssetPlugin::default(),
+            bevy::scene::ScenePlugin,
+            PhysicsPlugins::default().with_length_unit(PHYSICS_LENGTH_UNIT),
+            LanderPhysicsPlugin,
+        ))
+        .init_asset::<Mesh>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / TICKS_PER_SECOND,
+        )))
+        .insert_resource(Time::<Fixed>::from_hz(TICKS_PER_SECOND))
+        .insert_resource(Gravity(Vector::NEG_Y * GRAVITY))
+        .insert_resource(get_world_bounds_resource_2d())
+        .insert_resource(config.scores())
+        .init_resource::<LaunchConditions>()
+        .insert_state(AppState::Game)
+        .add_sub_state::<GameState>();
+        app.finish();
+        app.cleanup();
+        let landscape = app.world().resource::<WorldBoundsVertices2D>();
+        let terrain = landscape_world_vertices(landscape);
+        let sprite_image = Image::from_buffer(
+            include_bytes!("../assets/spaceship.png"),
+            ImageType::Extension("png"),
+            CompressedImageFormats::NONE,
+            true,
+            ImageSampler::Default,
+            RenderAssetUsages::default(),
+        )
+        .expect("failed to decode the spaceship image");
+        let world = app.world_mut();
+        let mut commands_queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut commands_queue, world);
+        spawn_landscape_colliders(&mut commands, world.resource::<WorldBoundsVertices2D>());
+        commands_queue.apply(world);
+        let lander = world
+            .spawn((
+                lander_physics_bundle(lander_collider(&sprite_image), config.gravity_scale),
+                TransformBundle::default(),
+            ))
+            .id();
+        Self {
+            app,
+            config,
+            lander,
+            terrain,
+            steps: 0,
+            done: true,
+            landed_events: ManualEventReader::default(),
+            explosion_events: ManualEventReader::default(),
+        }
+    }
+
+    /// Starts a new episode from launch conditions derived from `seed` and returns its first observation.
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        let launch_conditions = LaunchConditions::from_seed(seed);
+        // the clocks only start ticking on the first update, so prime them with the physics paused
+        self.app.world_mut().resource_mut::<Time<Physics>>().pause();
+        self.app.update();
+        let world = self.app.world_mut();
+        world.insert_resource(launch_conditions);
+        world.insert_resource(self.config.scores());
+        world.insert_resource(ThrusterTickInput::default());
+        let mut lander = world.entity_mut(self.lander);
+        lander.insert((
+            Transform::from_translation(launch_conditions.position.extend(2.0)),
+            Position(launch_conditions.position),
+            LinearVelocity(launch_conditions.velocity),
+            GravityScale(self.config.gravity_scale),
+            // contacts from the previous episode would end this one right away
+            CollidingEntities::default(),
+        ));
+        lander.remove::<ReadyToLand>();
+        world.resource_mut::<Time<Physics>>().unpause();
+        world
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Landing);
+        world.run_schedule(StateTransition);
+        // pending events belong to the previous episode
+        self.landed_events
+            .clear(world.resource::<Events<SpaceshipJustLandedEvent>>());
+        self.explosion_events
+            .clear(world.resource::<Events<SpawnExplosionEvent>>());
+        self.steps = 0;
+        self.done = false;
+        self.observation()
+    }
+
+    /// Holds `actions` during one fixed physics tick. Once the episode is over nothing moves, every
+    /// step is done with no reward until the next reset.
+    pub fn step(&mut self, actions: &[PlayerAction]) -> Step {
+        if self.done {
+            return Step {
+                observation: self.observation(),
+                reward: 0.0,
+                done: true,
+                info: StepInfo {
+                    steps: self.steps,
+                    fuel_used: 0.0,
+                    outcome: None,
+                },
+            };
+        }
+        let fuel_before = self.app.world().resource::<Scores>().fuel_quantity;
+        self.app
+            .world_mut()
+            .insert_resource(ThrusterTickInput::from_actions(actions));
+        self.app.update();
+        self.steps += 1;
+        let world = self.app.world();
+        let mut outcome = None;
+        let mut reward = 0.0;
+        if let Some(event) = self
+            .landed_events
+            .read(world.resource::<Events<SpaceshipJustLandedEvent>>())
+            .last()
+        {
+            let score = landing_score(event.platform.factor, event.linear_velocity.y);
+            reward = score as f32;
+            outcome = Some(Outcome::Landed {
+                platform_factor: event.platform.factor,
+                vertical_velocity: event.linear_velocity.y,
+                score,
+            });
+        }
+        if let Some(event) = self
+            .explosion_events
+            .read(world.resource::<Events<SpawnExplosionEvent>>())
+            .last()
+        {
+            reward = -CRASH_PENALTY;
+            outcome = Some(Outcome::Crashed { cause: event.cause });
+        }
+        if outcome.is_none() && self.steps >= self.config.max_steps {
+            outcome = Some(Outcome::TimeLimit);
+        }
+        self.done = outcome.is_some();
+        let fuel = world.resource::<Scores>().fuel_quantity;
+        Step {
+            observation: self.observation(),
+            reward,
+            done: self.done,
+            info: StepInfo {
+                steps: self.steps,
+                fuel_used: (fuel_before - fuel).max(0.0),
+                outcome,
+            },
+        }
+    }
+
+    pub fn observation(&self) -> Observation {
+        let world = self.app.world();
+        let lander = world.entity(self.lander);
+        let position = lander.get::<Transform>().unwrap().translation.xy();
+        let velocity = lander.get::<LinearVelocity>().unwrap().0;
+        let scores = world.resource::<Scores>();
+        let terrain_heights = TERRAIN_SAMPLE_OFFSETS
+            .iter()
+            .map(|offset| {
+                terrain_height_at(&self.terrain, position.x + offset)
+                    .map_or(position.y - WORLD_BOUNDS_MIN_Y, |terrain_y| {
+                        position.y - terrain_y
+                    })
+            })
+            .collect();
+        let mut platforms: Vec<PlatformObservation> = world
+            .iter_entities()
+            .filter_map(|entity| Some((entity.get::<Platform>()?, entity.get::<Transform>()?)))
+            .map(|(platform, transform)| PlatformObservation {
+                factor: platform.factor,
+                offset: transform.translation.xy() - position,
+                half_width: platform.width / 2.0,
+            })
+            .collect();
+        platforms.sort_by_key(|platform| platform.factor);
+        Observation {
+            position,
+            velocity,
+            fuel: scores.fuel_quantity,
+            gravity_scale: scores.gravity,
+            ready_to_land: lander.contains::<ReadyToLand>(),
+            terrain_heights,
+            platforms,
+        }
+    }
+
+    pub fn config(&self) -> &EnvironmentConfig {
+        &self.config
+    }
+}
+
+impl Default for LanderEnvironment {
+    fn default() -> Self {
+        Self::new(EnvironmentConfig::default())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentConfig {
+    pub gravity_scale: f32,
+    pub fuel: f32,
+    /// Fixed ticks before an episode is truncated
+    pub max_steps: u32,
+}
+
+impl EnvironmentConfig {
+    fn scores(&self) -> Scores {
+        Scores {
+            score: 0,
+            hi_score: 0,
+            fuel_quantity: self.fuel,
+            gravity: self.gravity_scale,
+            level: 1,
+        }
+    }
+}
+
+impl Default for EnvironmentConfig {
+    fn default() -> Self {
+        Self {
+            gravity_scale: 1.0,
+            fuel: FUEL_QUANTITY,
+            max_steps: 60 * TICKS_PER_SECOND as u32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Observation {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub fuel: f32,
+    pub gravity_scale: f32,
+    pub ready_to_land: bool,
+    /// Height above the terrain at every `TERRAIN_SAMPLE_OFFSETS` horizontal offset
+    pub terrain_heights: Vec<f32>,
+    pub platforms: Vec<PlatformObservation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlatformObservation {
+    pub factor: i32,
+    /// Platform center relative to the lander position
+    pub offset: Vec2,
+    pub half_width: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Step {
+    pub observation: Observation,
+    pub reward: f32,
+    pub done: bool,
+    pub info: StepInfo,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepInfo {
+    pub steps: u32,
+    pub fuel_used: f32,
+    pub outcome: Option<Outcome>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Outcome {
+    Landed {
+        platform_factor: i32,
+        vertical_velocity: f32,
+        score: i32,
+    },
+    Crashed {
+        cause: CrashCause,
+    },
+    TimeLimit,
+}
+
+#[test]
+fn free_fall_ends_with_a_crash_test() {
+    let mut environment = LanderEnvironment::default();
+    let first_observation = environment.reset(7);
+    assert_eq!(first_observation, environment.reset(7));
+    let mut step = environment.step(&[]);
+    while !step.done {
+        step = environment.step(&[]);
+    }
+    assert!(matches!(step.info.outcome, Some(Outcome::Crashed { .. })));
+    assert!(step.reward < 0.0);
+    // one step too many
+    let extra_step = environment.step(&[PlayerAction::MainThrusterBig]);
+    assert!(extra_step.done);
+    assert_eq!(extra_step.reward, 0.0);
+    assert_eq!(extra_step.observation, step.observation);
+}
+
+#[test]
+fn single_step_moves_the_lander_test() {
+    let mut environment = LanderEnvironment::default();
+    let observation = environment.reset(7);
+    let step = environment.step(&[PlayerAction::MainThrusterBig]);
+    assert!(
+        step.observation.position != observation.position
+            || step.observation.velocity != observation.velocity
+    );
+}
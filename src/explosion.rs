@@ -1,10 +1,13 @@
 use bevy::audio::PlaybackMode;
 use bevy::prelude::*;
+use bevy_persistent::Persistent;
+use serde::{Deserialize, Serialize};
 use std::f32::consts::TAU;
 
 use crate::asset_loader::{AudioAssets, SceneAssets};
 use crate::audio::{MusicBeginSoundEffect, MusicPlayingSoundEffect};
 use crate::game::Scores;
+use crate::settings::Settings;
 use crate::spaceship::Player;
 use crate::state::{AppState, GameState};
 
@@ -12,22 +15,21 @@ pub struct ExplosionPlugin;
 
 impl Plugin for ExplosionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<SpawnExplosionEvent>()
-            .add_event::<FinishedExplosionEvent>()
-            .add_systems(
-                Update,
-                (
-                    animate_explosion_system.run_if(in_state(GameState::Crashed)),
-                    catch_explosion_event_system.run_if(in_state(GameState::Crashed)),
-                    catch_finished_explosion_event_system.run_if(in_state(GameState::Crashed)),
-                ),
-            );
+        app.add_event::<FinishedExplosionEvent>().add_systems(
+            Update,
+            (
+                animate_explosion_system.run_if(in_state(GameState::Crashed)),
+                catch_explosion_event_system.run_if(in_state(GameState::Crashed)),
+                catch_finished_explosion_event_system.run_if(in_state(GameState::Crashed)),
+            ),
+        );
     }
 }
 
 fn catch_explosion_event_system(
     scene_assets: Res<SceneAssets>,
     audio_assets: Res<AudioAssets>,
+    settings: Res<Persistent<Settings>>,
     music_playing_controller: Query<&AudioSink, With<MusicPlayingSoundEffect>>,
     music_begin_controller: Query<&AudioSink, With<MusicBeginSoundEffect>>,
     mut commands: Commands,
@@ -65,6 +67,7 @@ fn catch_explosion_event_system(
                 source: audio_assets.ship_explosion.clone(),
                 settings: PlaybackSettings {
                     mode: PlaybackMode::Despawn,
+                    volume: settings.sfx_volume(),
                     ..default()
                 },
             },
@@ -111,7 +114,6 @@ fn catch_finished_explosion_event_system(
     if !event_reader.is_empty() {
         scores.fuel_quantity -= 100.0;
         if scores.fuel_quantity <= 0.0 {
-            scores.score = 0;
             game_state.set(GameState::GameOver);
         } else {
             game_state.set(GameState::Setup);
@@ -124,6 +126,19 @@ fn catch_finished_explosion_event_system(
 pub struct SpawnExplosionEvent {
     pub x: f32,
     pub y: f32,
+    pub cause: CrashCause,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum CrashCause {
+    /// Touched a platform outside the landing speed window
+    TooFast,
+    /// Touched the landscape within the landing speed window
+    OffPlatform,
+    /// Touched the landscape outside the landing speed window
+    Terrain,
+    /// Flew out of the world bounds
+    WorldBounds,
 }
 
 #[derive(Event)]
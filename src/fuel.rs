@@ -4,7 +4,13 @@ use bevy::sprite::*;
 
 use crate::asset_loader::UiAssets;
 use crate::game::{Scores, FUEL_QUANTITY};
+use crate::localization::{Localization, LocalizedText};
+use crate::notification::ShowToastEvent;
 use crate::state::{AppState, GameState};
+use crate::{WINDOW_HEIGHT, WINDOW_WIDTH};
+
+/// Below this quantity the player is warned once with a toast.
+const LOW_FUEL_QUANTITY: f32 = FUEL_QUANTITY / 5.0;
 
 pub struct FuelPlugin;
 
@@ -16,7 +22,7 @@ impl Plugin for FuelPlugin {
         )
         .add_systems(
             Update,
-            update_fuel_bar_system.run_if(in_state(GameState::Landing)),
+            (update_fuel_bar_system, warn_low_fuel_system).run_if(in_state(GameState::Landing)),
         );
     }
 }
@@ -26,7 +32,11 @@ fn spawn_fuel_bar_system(mut commands: Commands) {
     commands.spawn((
         StateScoped(AppState::Game),
         SpriteBundle {
-            transform: Transform::from_translation(Vec3::new(-500.0, -340.0, 3.0)),
+            transform: Transform::from_translation(Vec3::new(
+                12.0 - WINDOW_WIDTH / 2.0,
+                20.0 - WINDOW_HEIGHT / 2.0,
+                3.0,
+            )),
             sprite: Sprite {
                 anchor: Anchor::CenterLeft,
                 color: Color::srgb(0.19, 0.10, 0.84),
@@ -40,11 +50,16 @@ fn spawn_fuel_bar_system(mut commands: Commands) {
     ));
 }
 
-fn spawn_fuel_bar_text_system(mut commands: Commands, assets: ResMut<UiAssets>) {
+fn spawn_fuel_bar_text_system(
+    mut commands: Commands,
+    assets: ResMut<UiAssets>,
+    localization: Res<Localization>,
+) {
     commands.spawn((
         StateScoped(AppState::Game),
+        LocalizedText("hud.fuel"),
         TextBundle::from_section(
-            "Fuel",
+            localization.text("hud.fuel"),
             TextStyle {
                 font: assets.font_vt323.clone(),
                 ..default()
@@ -69,6 +84,20 @@ fn update_fuel_bar_system(
     fuel_bar_sprite.custom_size = Some(Vec2::new(scores.fuel_quantity, 15.0));
 }
 
+fn warn_low_fuel_system(
+    scores: Res<Scores>,
+    localization: Res<Localization>,
+    mut toast_events: EventWriter<ShowToastEvent>,
+    mut warned: Local<bool>,
+) {
+    let low_fuel = scores.fuel_quantity < LOW_FUEL_QUANTITY;
+    if low_fuel && !*warned && scores.fuel_quantity > 0.0 {
+        toast_events.send(ShowToastEvent(localization.text("toast.low_fuel").into()));
+    }
+    // refuelling after a landing warns again next time
+    *warned = low_fuel;
+}
+
 // Components
 #[derive(Component, Debug)]
 pub struct FuelBar;
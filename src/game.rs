@@ -1,91 +1,73 @@
+use crate::accessibility::background_rotation_enabled;
 use crate::asset_loader::{MusicAssets, SceneAssets, UiAssets};
+use crate::assists::Assists;
+use crate::attract::AttractMode;
 use crate::audio::{MusicBeginSoundEffect, MusicPlayingSoundEffect};
 use crate::collider::Platform;
+use crate::leaderboard::Leaderboard;
+use crate::localization::{Localization, LocalizedText};
 use crate::menu::BLACK_COLOR;
+use crate::notification::{PanelDismiss, ShowPanelEvent, ShowToastEvent};
+use crate::settings::Settings;
 use crate::spaceship::{AirScapeSoundEffect, Player, ThrusterSoundEffect};
 use crate::state::{AppState, GameState};
-use crate::WINDOW_HEIGHT;
+use crate::{HUD_BOTTOM_BAR_HEIGHT, HUD_RIGHT_BAR_WIDTH, WINDOW_HEIGHT, WINDOW_WIDTH};
 use avian2d::prelude::{GravityScale, LinearVelocity};
-use bevy::app::AppExit;
 use bevy::audio::PlaybackMode;
-use bevy::input::common_conditions::*;
 use bevy::prelude::*;
 use bevy::render::view::RenderLayers;
-use bevy::text::Text2dBounds;
-use bevy_persistent::{Persistent, StorageFormat};
+use bevy_persistent::Persistent;
 use rand::prelude::*;
-use serde::{Deserialize, Serialize};
 use std::f32::consts::TAU;
 
 pub const FUEL_QUANTITY: f32 = 1000.0;
-const INFO_PANEL_WIDTH: f32 = 400.0;
-const INFO_PANEL_HEIGHT: f32 = 110.0;
-const INFO_PANEL_SIZE: Vec2 = Vec2::new(INFO_PANEL_WIDTH, INFO_PANEL_HEIGHT);
-const INFO_PANEL_POSITION: Vec2 = Vec2::new(0.0, WINDOW_HEIGHT / 4.0);
+/// Gravity added on every landing, as the level goes up.
+const GRAVITY_STEP: f32 = 0.1;
 
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Scores {
-            score: 0,
-            hi_score: 0,
-            fuel_quantity: FUEL_QUANTITY,
-            gravity: 1.0,
-        })
-        .add_event::<SpaceshipJustLandedEvent>()
-        .add_event::<OutOfFuelEvent>()
-        .add_systems(Startup, persist_hi_score)
-        .add_systems(
-            OnEnter(AppState::Menu),
-            spawn_rusty_planet_menu_background_image_and_intro_music_system,
-        )
-        .add_systems(
-            OnEnter(AppState::Game),
-            (spawn_background_image_system, spawn_scores_text_system),
-        )
-        .add_systems(
-            Update,
-            (
-                rotate_background_image_system,
-                update_scoring_text_system.run_if(in_state(GameState::Landed)),
-                catch_spaceship_just_landed_event_system.run_if(in_state(GameState::Landed)),
-                catch_out_of_fuel_event_system.run_if(in_state(GameState::Landing)),
-                handle_any_control_key_has_been_pressed_system.run_if(in_state(GameState::Landed)),
-                handle_exit_key_pressed_system.run_if(input_just_pressed(KeyCode::Escape)),
-            ),
-        );
+        app.init_resource::<Scores>()
+            .add_systems(
+                OnEnter(AppState::Menu),
+                spawn_rusty_planet_menu_background_image_and_intro_music_system,
+            )
+            .add_systems(
+                OnEnter(AppState::Game),
+                (spawn_background_image_system, spawn_scores_text_system),
+            )
+            .add_systems(
+                Update,
+                (
+                    rotate_background_image_system.run_if(background_rotation_enabled),
+                    update_scoring_text_system.run_if(in_state(GameState::Landed)),
+                    catch_spaceship_just_landed_event_system.run_if(in_state(GameState::Landed)),
+                    catch_out_of_fuel_event_system.run_if(in_state(GameState::Landing)),
+                    handle_any_control_key_has_been_pressed_system
+                        .run_if(in_state(GameState::Landed)),
+                ),
+            );
     }
 }
 
 // Systems
-fn persist_hi_score(mut commands: Commands) {
-    let config_dir = dirs::config_dir().unwrap().join("RustyLander");
-    commands.insert_resource(
-        Persistent::<BestScoreSoFar>::builder()
-            .name("scores")
-            .format(StorageFormat::Json)
-            .path(config_dir.join("scores.json"))
-            .default(BestScoreSoFar {
-                hi_score: 0,
-                gravity: 1.0,
-            })
-            .build()
-            .expect("failed to initialize initial scores"),
-    )
-}
-
 fn catch_spaceship_just_landed_event_system(
-    assets: ResMut<UiAssets>,
     music_assets: Res<MusicAssets>,
+    settings: Res<Persistent<Settings>>,
     air_scape_sound_controller: Query<&AudioSink, With<AirScapeSoundEffect>>,
     thruster_sound_controller: Query<&AudioSink, With<ThrusterSoundEffect>>,
     music_begin_controller: Query<&AudioSink, With<MusicBeginSoundEffect>>,
     music_playing_controller: Query<&AudioSink, With<MusicPlayingSoundEffect>>,
+    attract_mode: Res<AttractMode>,
+    assists: Res<Assists>,
+    localization: Res<Localization>,
     mut events_reader: EventReader<SpaceshipJustLandedEvent>,
+    mut panel_events: EventWriter<ShowPanelEvent>,
+    mut toast_events: EventWriter<ShowToastEvent>,
     mut spaceship_gravity_query: Query<&mut GravityScale, With<Player>>,
     mut commands: Commands,
-    mut best_score_so_far: ResMut<Persistent<BestScoreSoFar>>,
+    leaderboard: Res<Persistent<Leaderboard>>,
     mut scores: ResMut<Scores>,
 ) {
     if let Ok(sink) = air_scape_sound_controller.get_single() {
@@ -103,80 +85,37 @@ fn catch_spaceship_just_landed_event_system(
     for event in events_reader.read() {
         let platform = event.platform.clone();
         let linear_velocity = event.linear_velocity;
-        let points = (14.57 * linear_velocity.y) as i32 + 720;
-        let new_score = platform.factor * points;
-        scores.score += new_score;
-        if best_score_so_far.hi_score < scores.score {
-            scores.hi_score = scores.score;
+        let points = landing_points(linear_velocity.y);
+        let assist_label = if event.autopilot {
+            " x 0".to_string()
+        } else if assists.score_multiplier() < 1.0 {
+            format!(" x {}", assists.score_multiplier())
         } else {
-            scores.hi_score = best_score_so_far.hi_score;
-        }
-        commands
-            .spawn((
-                StateScoped(GameState::Landing),
-                Resettable,
-                SpriteBundle {
-                    sprite: Sprite {
-                        color: BLACK_COLOR,
-                        custom_size: Some(INFO_PANEL_SIZE),
-                        ..default()
-                    },
-                    transform: Transform::from_translation(INFO_PANEL_POSITION.extend(11.0)),
+            "".into()
+        };
+        let new_score = event.score;
+        scores.score += new_score;
+        let best_score = leaderboard.best_score();
+        scores.hi_score = scores.score.max(best_score);
+        commands.spawn((
+            Resettable,
+            AudioBundle {
+                source: music_assets.music_end.clone(),
+                settings: PlaybackSettings {
+                    mode: PlaybackMode::Once,
+                    volume: settings.music_volume(),
                     ..default()
                 },
-                AudioBundle {
-                    source: music_assets.music_end.clone(),
-                    settings: PlaybackSettings {
-                        mode: PlaybackMode::Once,
-                        ..default()
-                    },
-                },
-                RenderLayers::layer(2),
-            ))
-            .with_children(|builder| {
-                builder.spawn((
-                    Text2dBundle {
-                        text: Text::from_section(
-                            points.to_string()
-                                + " x "
-                                + platform.factor.to_string().as_str()
-                                + " = "
-                                + new_score.to_string().as_str(),
-                            TextStyle {
-                                font: assets.font_vt323.clone(),
-                                font_size: 60.0,
-                                color: Color::WHITE,
-                            },
-                        )
-                        .with_justify(JustifyText::Left),
-                        text_2d_bounds: Text2dBounds {
-                            size: INFO_PANEL_SIZE,
-                        },
-                        transform: Transform::from_translation(Vec3::new(0.0, 20.0, 1.0)),
-                        ..default()
-                    },
-                    RenderLayers::layer(2),
-                ));
-                builder.spawn((
-                    Text2dBundle {
-                        text: Text::from_section(
-                            "press enter key to continue",
-                            TextStyle {
-                                font: assets.font_vt323.clone(),
-                                font_size: 30.0,
-                                color: Color::WHITE,
-                            },
-                        )
-                        .with_justify(JustifyText::Left),
-                        text_2d_bounds: Text2dBounds {
-                            size: INFO_PANEL_SIZE,
-                        },
-                        transform: Transform::from_translation(Vec3::new(0.0, -20.0, 1.0)),
-                        ..default()
-                    },
-                    RenderLayers::layer(2),
-                ));
-            });
+            },
+        ));
+        panel_events.send(ShowPanelEvent {
+            title: format!(
+                "{} x {}{} = {}",
+                points, platform.factor, assist_label, new_score
+            ),
+            lines: vec![localization.text("game.press_enter").into()],
+            dismiss: PanelDismiss::OnKey,
+        });
         let mut new_fuel_quantity = (new_score as f32) / 20.0;
         if new_fuel_quantity > scores.get_available_fuel_quantity() {
             new_fuel_quantity = scores.get_available_fuel_quantity();
@@ -185,67 +124,33 @@ fn catch_spaceship_just_landed_event_system(
         let Ok(mut spaceship_gravity) = spaceship_gravity_query.get_single_mut() else {
             return;
         };
-        scores.gravity += 0.1;
+        scores.gravity += GRAVITY_STEP;
+        scores.level += 1;
         spaceship_gravity.0 = scores.gravity;
-        if scores.score > best_score_so_far.hi_score {
-            best_score_so_far
-                .update(|best_score_so_far| {
-                    best_score_so_far.hi_score = scores.score;
-                })
-                .expect("failed to update best_score_so_far gravity");
+        if attract_mode.active {
+            break;
         }
-        if scores.gravity > best_score_so_far.gravity {
-            best_score_so_far
-                .update(|best_score_so_far| {
-                    best_score_so_far.gravity = scores.gravity;
-                })
-                .expect("failed to update best_score_so_far gravity");
+        // only once per game, when the score goes past the best one, recorded on game over
+        if scores.score > best_score && scores.score - new_score <= best_score && best_score > 0 {
+            toast_events.send(ShowToastEvent(
+                localization.text("toast.new_hi_score").into(),
+            ));
         }
         break;
     }
 }
 
 fn catch_out_of_fuel_event_system(
-    assets: ResMut<UiAssets>,
+    localization: Res<Localization>,
     mut events_reader: EventReader<OutOfFuelEvent>,
-    mut commands: Commands,
+    mut panel_events: EventWriter<ShowPanelEvent>,
 ) {
     for _event in events_reader.read() {
-        commands
-            .spawn((
-                Resettable,
-                SpriteBundle {
-                    sprite: Sprite {
-                        color: BLACK_COLOR,
-                        custom_size: Some(INFO_PANEL_SIZE),
-                        ..default()
-                    },
-                    transform: Transform::from_translation(INFO_PANEL_POSITION.extend(11.0)),
-                    ..default()
-                },
-                RenderLayers::layer(2),
-            ))
-            .with_children(|builder| {
-                builder.spawn((
-                    Text2dBundle {
-                        text: Text::from_section(
-                            "Out of fuel",
-                            TextStyle {
-                                font: assets.font_vt323.clone(),
-                                font_size: 60.0,
-                                color: Color::WHITE,
-                            },
-                        )
-                        .with_justify(JustifyText::Left),
-                        text_2d_bounds: Text2dBounds {
-                            size: INFO_PANEL_SIZE,
-                        },
-                        transform: Transform::from_translation(Vec3::Z),
-                        ..default()
-                    },
-                    RenderLayers::layer(2),
-                ));
-            });
+        panel_events.send(ShowPanelEvent {
+            title: localization.text("game.out_of_fuel").into(),
+            lines: vec![],
+            dismiss: PanelDismiss::OnKey,
+        });
     }
 }
 
@@ -268,16 +173,21 @@ fn spawn_scores_text_system(
     mut commands: Commands,
     assets: ResMut<UiAssets>,
     scores: Res<Scores>,
-    best_score_so_far: Res<Persistent<BestScoreSoFar>>,
+    leaderboard: Res<Persistent<Leaderboard>>,
+    localization: Res<Localization>,
 ) {
     // black background UI horizontal
     commands.spawn((
         StateScoped(AppState::Game),
         SpriteBundle {
-            transform: Transform::from_translation(Vec3::new(0.0, -330.0, 2.0)),
+            transform: Transform::from_translation(Vec3::new(
+                0.0,
+                (HUD_BOTTOM_BAR_HEIGHT - WINDOW_HEIGHT) / 2.0,
+                2.0,
+            )),
             sprite: Sprite {
                 color: BLACK_COLOR,
-                custom_size: Some(Vec2::new(1024.0, 60.0)),
+                custom_size: Some(Vec2::new(WINDOW_WIDTH, HUD_BOTTOM_BAR_HEIGHT)),
                 ..default()
             },
             ..default()
@@ -288,10 +198,14 @@ fn spawn_scores_text_system(
     commands.spawn((
         StateScoped(AppState::Game),
         SpriteBundle {
-            transform: Transform::from_translation(Vec3::new(485.0, 0.0, 2.0)),
+            transform: Transform::from_translation(Vec3::new(
+                (WINDOW_WIDTH - HUD_RIGHT_BAR_WIDTH) / 2.0,
+                0.0,
+                2.0,
+            )),
             sprite: Sprite {
                 color: BLACK_COLOR,
-                custom_size: Some(Vec2::new(54.0, 720.0)),
+                custom_size: Some(Vec2::new(HUD_RIGHT_BAR_WIDTH, WINDOW_HEIGHT)),
                 ..default()
             },
             ..default()
@@ -301,8 +215,9 @@ fn spawn_scores_text_system(
     // speedometer UI texts
     commands.spawn((
         StateScoped(AppState::Game),
+        LocalizedText("hud.speed_unit"),
         TextBundle::from_section(
-            "m/s",
+            localization.text("hud.speed_unit"),
             TextStyle {
                 font: assets.font_vt323.clone(),
                 font_size: 20.0,
@@ -312,15 +227,16 @@ fn spawn_scores_text_system(
         .with_style(Style {
             position_type: PositionType::Absolute,
             top: Val::Px(36.0),
-            right: Val::Px(16.0),
+            right: Val::Px(2.0),
             ..default()
         }),
     ));
     // scoring UI texts
     commands.spawn((
         StateScoped(AppState::Game),
+        LocalizedText("hud.score"),
         TextBundle::from_section(
-            "Score",
+            localization.text("hud.score"),
             TextStyle {
                 font: assets.font_vt323.clone(),
                 ..default()
@@ -352,8 +268,9 @@ fn spawn_scores_text_system(
     ));
     commands.spawn((
         StateScoped(AppState::Game),
+        LocalizedText("hud.high_score"),
         TextBundle::from_section(
-            "High Score",
+            localization.text("hud.high_score"),
             TextStyle {
                 font: assets.font_vt323.clone(),
                 ..default()
@@ -370,7 +287,7 @@ fn spawn_scores_text_system(
         StateScoped(AppState::Game),
         TextHiScore,
         TextBundle::from_section(
-            best_score_so_far.hi_score.to_string(),
+            leaderboard.best_score().to_string(),
             TextStyle {
                 font: assets.font_vt323.clone(),
                 ..default()
@@ -389,6 +306,7 @@ fn spawn_rusty_planet_menu_background_image_and_intro_music_system(
     mut commands: Commands,
     scene_assets: Res<SceneAssets>,
     music_assets: Res<MusicAssets>,
+    settings: Res<Persistent<Settings>>,
 ) {
     commands.spawn((
         StateScoped(AppState::Menu),
@@ -404,6 +322,7 @@ fn spawn_rusty_planet_menu_background_image_and_intro_music_system(
             source: music_assets.music_intro.clone(),
             settings: PlaybackSettings {
                 mode: PlaybackMode::Loop,
+                volume: settings.music_volume(),
                 ..default()
             },
         },
@@ -439,11 +358,6 @@ fn rotate_background_image_system(
     transform.rotate_z(background.speed * TAU * timer.delta_seconds());
 }
 
-fn handle_exit_key_pressed_system(mut exit: EventWriter<AppExit>) {
-    info!("exit key has been pressed");
-    exit.send(AppExit::Success);
-}
-
 fn handle_any_control_key_has_been_pressed_system(
     inputs: Res<ButtonInput<KeyCode>>,
     resettable_text_query: Query<Entity, With<Resettable>>,
@@ -461,9 +375,34 @@ fn handle_any_control_key_has_been_pressed_system(
     }
 }
 
+/// Points earned by a touchdown at `vertical_velocity`, before applying the platform factor.
+pub fn landing_points(vertical_velocity: f32) -> i32 {
+    (14.57 * vertical_velocity) as i32 + 720
+}
+
+pub fn landing_score(platform_factor: i32, vertical_velocity: f32) -> i32 {
+    platform_factor * landing_points(vertical_velocity)
+}
+
+/// Points awarded for a landing: reduced by the assists used, nothing when the autopilot flew.
+pub fn awarded_landing_score(
+    platform_factor: i32,
+    vertical_velocity: f32,
+    assists: Option<&Assists>,
+    autopilot_engaged: bool,
+) -> i32 {
+    let score = landing_score(platform_factor, vertical_velocity);
+    match assists {
+        _ if autopilot_engaged => 0,
+        Some(assists) => assists.apply_to_score(score),
+        None => score,
+    }
+}
+
 // Sets
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum InGameSet {
+    Input,
     Physics,
     Collisions,
     SpeedBar,
@@ -474,6 +413,10 @@ pub enum InGameSet {
 pub struct SpaceshipJustLandedEvent {
     pub platform: Platform,
     pub linear_velocity: LinearVelocity,
+    /// The autopilot flew during the attempt, the landing scores nothing
+    pub autopilot: bool,
+    /// Points awarded, see `awarded_landing_score`
+    pub score: i32,
 }
 
 #[derive(Event)]
@@ -503,24 +446,38 @@ pub struct WorldBoundsVertices2D {
     pub data: Vec<Vec2>,
 }
 
-#[derive(Resource, Serialize, Deserialize, Debug)]
-pub struct BestScoreSoFar {
-    pub hi_score: i32,
-    pub gravity: f32,
-}
-
-#[derive(Resource, Debug)]
+#[derive(Resource, Debug, Clone)]
 pub struct Scores {
     pub score: i32,
     pub hi_score: i32,
     pub fuel_quantity: f32,
     pub gravity: f32,
+    /// Starts at 1 and goes up with every landing, as the gravity does
+    pub level: u32,
+}
+
+impl Default for Scores {
+    fn default() -> Self {
+        Self {
+            score: 0,
+            hi_score: 0,
+            fuel_quantity: FUEL_QUANTITY,
+            gravity: 1.0,
+            level: 1,
+        }
+    }
 }
 
 impl Scores {
     pub fn get_available_fuel_quantity(&self) -> f32 {
         FUEL_QUANTITY - self.fuel_quantity
     }
+
+    /// Starts the game at `level`, with the gravity the landings before it would have reached.
+    pub fn start_at_level(&mut self, level: u32) {
+        self.level = level.max(1);
+        self.gravity = 1.0 + GRAVITY_STEP * (self.level - 1) as f32;
+    }
 }
 
 #[test]
@@ -528,3 +485,22 @@ fn dummy_test() {
     let test = 1;
     assert_eq!(test, 1);
 }
+
+#[test]
+fn awarded_landing_score_test() {
+    let score = landing_score(5, -10.0);
+    let assists = Assists {
+        trajectory: false,
+        used: true,
+    };
+    assert_eq!(awarded_landing_score(5, -10.0, None, false), score);
+    assert_eq!(
+        awarded_landing_score(5, -10.0, Some(&Assists::default()), false),
+        score
+    );
+    assert_eq!(
+        awarded_landing_score(5, -10.0, Some(&assists), false),
+        (score as f32 * crate::assists::TRAJECTORY_SCORE_MULTIPLIER).round() as i32
+    );
+    assert_eq!(awarded_landing_score(5, -10.0, None, true), 0);
+}
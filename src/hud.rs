@@ -0,0 +1,306 @@
+use avian2d::prelude::LinearVelocity;
+use bevy::color::palettes::css::ORANGE;
+use bevy::prelude::*;
+use bevy_persistent::Persistent;
+
+use crate::asset_loader::UiAssets;
+use crate::autopilot::LandingPad;
+use crate::collider::Platform;
+use crate::game::Scores;
+use crate::input_buffer::ThrusterTickInput;
+use crate::localization::{Localization, LocalizedText};
+use crate::movement::{BIG_THRUST, MEDIUM_THRUST, SMALL_THRUST};
+use crate::radar::{RadarAltimeter, RadarSurface};
+use crate::settings::Settings;
+use crate::spaceship::{Player, PlayerAction};
+use crate::state::{AppState, GameState};
+
+const TIME_TO_IMPACT_WARNING: f32 = 3.0;
+/// Instruments stacked in the right black bar, next to the speed bar.
+const PANEL_INSTRUMENTS: [Instrument; 6] = [
+    Instrument::VerticalVelocity,
+    Instrument::HorizontalVelocity,
+    Instrument::Altitude,
+    Instrument::PlatformDistance,
+    Instrument::PlatformBearing,
+    Instrument::Throttle,
+];
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlightTime>()
+            .add_systems(OnEnter(AppState::Game), spawn_instruments_system)
+            .add_systems(OnEnter(GameState::Setup), reset_flight_time_system)
+            .add_systems(
+                Update,
+                (tick_flight_time_system, update_instruments_system)
+                    .chain()
+                    .run_if(in_state(GameState::Landing)),
+            )
+            .add_systems(
+                Update,
+                update_instrument_labels_color_system
+                    .run_if(resource_changed::<Persistent<Settings>>),
+            );
+    }
+}
+
+// Systems
+fn spawn_instruments_system(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    localization: Res<Localization>,
+    settings: Res<Persistent<Settings>>,
+) {
+    // right black bar, a column of label and value pairs left of the speed bar
+    commands
+        .spawn((
+            StateScoped(AppState::Game),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(70.0),
+                    right: Val::Px(18.0),
+                    width: Val::Px(34.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(6.0),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            for instrument in PANEL_INSTRUMENTS {
+                parent.spawn((
+                    InstrumentLabel,
+                    LocalizedText(instrument.label_key()),
+                    TextBundle::from_section(
+                        localization.text(instrument.label_key()),
+                        TextStyle {
+                            font: assets.font_vt323.clone(),
+                            font_size: 16.0,
+                            color: settings.accessibility.hud_label_color(),
+                        },
+                    ),
+                ));
+                parent.spawn((
+                    instrument,
+                    TextBundle::from_section(
+                        "---",
+                        TextStyle {
+                            font: assets.font_vt323.clone(),
+                            font_size: 20.0,
+                            ..default()
+                        },
+                    ),
+                ));
+            }
+        });
+    // bottom black bar, right of the high score, with room for the longest translated labels
+    for (instrument, left) in [
+        (Instrument::FlightTime, 640.0),
+        (Instrument::Gravity, 815.0),
+    ] {
+        commands.spawn((
+            StateScoped(AppState::Game),
+            LocalizedText(instrument.label_key()),
+            TextBundle::from_section(
+                localization.text(instrument.label_key()),
+                TextStyle {
+                    font: assets.font_vt323.clone(),
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(33.0),
+                left: Val::Px(left),
+                ..default()
+            }),
+        ));
+        commands.spawn((
+            StateScoped(AppState::Game),
+            instrument,
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: assets.font_vt323.clone(),
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(33.0),
+                left: Val::Px(left + 90.0),
+                ..default()
+            }),
+        ));
+    }
+}
+
+fn update_instrument_labels_color_system(
+    settings: Res<Persistent<Settings>>,
+    mut labels_query: Query<&mut Text, With<InstrumentLabel>>,
+) {
+    for mut text in labels_query.iter_mut() {
+        text.sections[0].style.color = settings.accessibility.hud_label_color();
+    }
+}
+
+fn reset_flight_time_system(mut flight_time: ResMut<FlightTime>) {
+    flight_time.0 = 0.0;
+}
+
+fn tick_flight_time_system(time: Res<Time>, mut flight_time: ResMut<FlightTime>) {
+    flight_time.0 += time.delta_seconds();
+}
+
+fn update_instruments_system(
+    scores: Res<Scores>,
+    flight_time: Res<FlightTime>,
+    tick_input: Res<ThrusterTickInput>,
+    platforms_query: Query<(&Platform, &Transform)>,
+    spaceship_query: Query<(&Transform, &LinearVelocity, &RadarAltimeter), With<Player>>,
+    mut instruments_query: Query<(&Instrument, &mut Text)>,
+) {
+    let Ok((transform, linear_velocity, radar_altimeter)) = spaceship_query.get_single() else {
+        return;
+    };
+    let position = transform.translation.xy();
+    let nearest_platform = platforms_query
+        .iter()
+        .map(|(platform, transform)| LandingPad::new(platform, transform))
+        .min_by(|a, b| {
+            a.center
+                .distance_squared(position)
+                .total_cmp(&b.center.distance_squared(position))
+        });
+    for (instrument, mut text) in instruments_query.iter_mut() {
+        let section = &mut text.sections[0];
+        section.style.color = Color::WHITE;
+        section.value = match instrument {
+            Instrument::VerticalVelocity => format!("{:.0}", linear_velocity.y),
+            Instrument::HorizontalVelocity => format!("{:.0}", linear_velocity.x),
+            Instrument::Altitude => match radar_altimeter.contact {
+                Some(contact) => {
+                    if contact
+                        .time_to_impact
+                        .is_some_and(|seconds| seconds < TIME_TO_IMPACT_WARNING)
+                    {
+                        section.style.color = Color::from(ORANGE);
+                    }
+                    match contact.surface {
+                        RadarSurface::Platform(factor) => {
+                            format!("{:.0}\nx{}", contact.height, factor)
+                        }
+                        _ => format!("{:.0}", contact.height),
+                    }
+                }
+                None => "---".into(),
+            },
+            Instrument::PlatformDistance => match nearest_platform {
+                Some(pad) => format!("{:.0}", pad.center.distance(position)),
+                None => "---".into(),
+            },
+            Instrument::PlatformBearing => match nearest_platform {
+                Some(pad) => format!("{:03.0}", bearing_degrees(pad.center - position)),
+                None => "---".into(),
+            },
+            Instrument::Throttle => format!("{:.0}%", throttle(&tick_input) * 100.0),
+            Instrument::FlightTime => format_flight_time(flight_time.0),
+            Instrument::Gravity => format!("x{:.1}", scores.gravity),
+        };
+    }
+}
+
+/// Compass like bearing of `offset`, 0 straight up and growing clockwise, 180 straight down.
+pub fn bearing_degrees(offset: Vec2) -> f32 {
+    offset.x.atan2(offset.y).to_degrees().rem_euclid(360.0)
+}
+
+/// Main thruster output of the current fixed tick, between 0 and 1. The thrusters add up, so full
+/// throttle is every main thruster fired together.
+pub fn throttle(tick_input: &ThrusterTickInput) -> f32 {
+    let main_thrusters = [
+        (PlayerAction::MainThrusterBig, BIG_THRUST),
+        (PlayerAction::MainThrusterMedium, MEDIUM_THRUST),
+        (PlayerAction::MainThrusterSmall, SMALL_THRUST),
+    ];
+    let full_thrust: f32 = main_thrusters.iter().map(|(_, thrust)| thrust).sum();
+    let thrust: f32 = main_thrusters
+        .iter()
+        .filter(|(action, _)| tick_input.pressed(*action))
+        .map(|(_, thrust)| thrust)
+        .sum();
+    thrust / full_thrust
+}
+
+fn format_flight_time(seconds: f32) -> String {
+    let seconds = seconds as u32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+// Resources
+/// Seconds flown since the lander was launched for the current landing attempt.
+#[derive(Resource, Debug, Default)]
+pub struct FlightTime(pub f32);
+
+// Components
+/// Instrument panel label, brighter with the high contrast HUD.
+#[derive(Component, Debug)]
+struct InstrumentLabel;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum Instrument {
+    VerticalVelocity,
+    HorizontalVelocity,
+    Altitude,
+    PlatformDistance,
+    PlatformBearing,
+    Throttle,
+    FlightTime,
+    Gravity,
+}
+
+impl Instrument {
+    /// Localization key of the instrument label.
+    fn label_key(&self) -> &'static str {
+        match self {
+            Instrument::VerticalVelocity => "hud.vertical_velocity",
+            Instrument::HorizontalVelocity => "hud.horizontal_velocity",
+            Instrument::Altitude => "hud.altitude",
+            Instrument::PlatformDistance => "hud.platform_distance",
+            Instrument::PlatformBearing => "hud.platform_bearing",
+            Instrument::Throttle => "hud.throttle",
+            Instrument::FlightTime => "hud.time",
+            Instrument::Gravity => "hud.gravity",
+        }
+    }
+}
+
+#[test]
+fn instruments_readings_test() {
+    assert_eq!(bearing_degrees(Vec2::Y), 0.0);
+    assert_eq!(bearing_degrees(Vec2::X), 90.0);
+    assert_eq!(bearing_degrees(-Vec2::Y), 180.0);
+    assert_eq!(bearing_degrees(-Vec2::X), 270.0);
+    assert_eq!(throttle(&ThrusterTickInput::default()), 0.0);
+    let tick_input = ThrusterTickInput::from_actions(&[PlayerAction::MainThrusterBig]);
+    assert!((throttle(&tick_input) - BIG_THRUST / 1.75).abs() < 1e-6);
+    // stacked thrusters add up
+    let tick_input = ThrusterTickInput::from_actions(&[
+        PlayerAction::MainThrusterSmall,
+        PlayerAction::MainThrusterBig,
+    ]);
+    assert!((throttle(&tick_input) - (SMALL_THRUST + BIG_THRUST) / 1.75).abs() < 1e-6);
+    let tick_input = ThrusterTickInput::from_actions(&[
+        PlayerAction::MainThrusterSmall,
+        PlayerAction::MainThrusterMedium,
+        PlayerAction::MainThrusterBig,
+    ]);
+    assert!((throttle(&tick_input) - 1.0).abs() < 1e-6);
+    assert_eq!(format_flight_time(75.4), "1:15");
+}
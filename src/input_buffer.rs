@@ -0,0 +1,137 @@
+use bevy::input::gamepad::GamepadButton;
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_persistent::Persistent;
+use leafwing_input_manager::prelude::*;
+
+use crate::controls::KeyBindings;
+use crate::game::InGameSet;
+use crate::spaceship::{Player, PlayerAction};
+
+pub const THRUSTER_ACTIONS: [PlayerAction; 5] = [
+    PlayerAction::LeftThruster,
+    PlayerAction::RightThruster,
+    PlayerAction::MainThrusterBig,
+    PlayerAction::MainThrusterMedium,
+    PlayerAction::MainThrusterSmall,
+];
+
+pub struct InputBufferPlugin;
+
+impl Plugin for InputBufferPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ThrusterInputBuffer>()
+            .add_systems(
+                PreUpdate,
+                accumulate_thruster_input_system.after(InputManagerSystem::ManualControl),
+            )
+            .add_systems(
+                FixedUpdate,
+                sample_thruster_input_system.in_set(InGameSet::Input),
+            );
+    }
+}
+
+// Systems
+fn accumulate_thruster_input_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    key_bindings: Option<Res<Persistent<KeyBindings>>>,
+    action_state_query: Query<&ActionState<PlayerAction>, With<Player>>,
+    mut buffer: ResMut<ThrusterInputBuffer>,
+) {
+    let Ok(action_state) = action_state_query.get_single() else {
+        return;
+    };
+    for action in THRUSTER_ACTIONS {
+        // a key pressed and released within the same frame is never seen as pressed by the action state
+        let tapped = key_bindings.as_ref().is_some_and(|key_bindings| {
+            key_bindings.player_action_just_pressed(action, &keys, &gamepad_buttons)
+        });
+        buffer.record(action, action_state.pressed(&action), tapped);
+    }
+}
+
+fn sample_thruster_input_system(
+    mut buffer: ResMut<ThrusterInputBuffer>,
+    mut tick_input: ResMut<ThrusterTickInput>,
+) {
+    *tick_input = buffer.take_tick_input();
+}
+
+// Resources
+/// Thruster actions recorded every frame since the last fixed tick.
+#[derive(Resource, Default, Debug)]
+pub struct ThrusterInputBuffer {
+    held: HashSet<PlayerAction>,
+    tapped: HashSet<PlayerAction>,
+}
+
+impl ThrusterInputBuffer {
+    pub fn record(&mut self, action: PlayerAction, pressed: bool, tapped: bool) {
+        if tapped || (pressed && !self.held.contains(&action)) {
+            self.tapped.insert(action);
+        }
+        if pressed {
+            self.held.insert(action);
+        } else {
+            self.held.remove(&action);
+        }
+    }
+
+    /// Builds the input seen by the next fixed tick. Taps are consumed, so they only last one tick.
+    pub fn take_tick_input(&mut self) -> ThrusterTickInput {
+        let tapped = std::mem::take(&mut self.tapped);
+        ThrusterTickInput {
+            pressed: self.held.union(&tapped).copied().collect(),
+            tapped,
+        }
+    }
+}
+
+/// Thruster actions to apply during the current fixed tick.
+#[derive(Resource, Default, Debug)]
+pub struct ThrusterTickInput {
+    pressed: HashSet<PlayerAction>,
+    tapped: HashSet<PlayerAction>,
+}
+
+impl ThrusterTickInput {
+    /// Tick input with `actions` held down, as sent by non interactive controllers.
+    pub fn from_actions(actions: &[PlayerAction]) -> Self {
+        Self {
+            pressed: actions.iter().copied().collect(),
+            tapped: HashSet::new(),
+        }
+    }
+
+    pub fn pressed(&self, action: PlayerAction) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn tapped(&self, action: PlayerAction) -> bool {
+        self.tapped.contains(&action)
+    }
+}
+
+#[test]
+fn short_tap_between_ticks_lasts_one_tick_test() {
+    let mut buffer = ThrusterInputBuffer::default();
+    // pressed and released on two frames between the same pair of fixed ticks
+    buffer.record(PlayerAction::MainThrusterSmall, true, true);
+    buffer.record(PlayerAction::MainThrusterSmall, false, false);
+    let tick_input = buffer.take_tick_input();
+    assert!(tick_input.pressed(PlayerAction::MainThrusterSmall));
+    assert!(tick_input.tapped(PlayerAction::MainThrusterSmall));
+    let tick_input = buffer.take_tick_input();
+    assert!(!tick_input.pressed(PlayerAction::MainThrusterSmall));
+    // held over several ticks is only tapped on the first one
+    buffer.record(PlayerAction::MainThrusterBig, true, false);
+    assert!(buffer
+        .take_tick_input()
+        .tapped(PlayerAction::MainThrusterBig));
+    buffer.record(PlayerAction::MainThrusterBig, true, false);
+    let tick_input = buffer.take_tick_input();
+    assert!(tick_input.pressed(PlayerAction::MainThrusterBig));
+    assert!(!tick_input.tapped(PlayerAction::MainThrusterBig));
+}
@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+
+use crate::settings::Difficulty;
+
+/// Vertical velocity half range shown by the speed bar, larger speeds are squeezed against the ends.
+pub const SPEED_BAR_HALF_HEIGHT: f32 = 300.0;
+/// Vertical velocity drawn at half the speed bar half height, below it the scale is almost linear.
+const SPEED_BAR_SCALE_KNEE: f32 = 100.0;
+
+// Resources
+/// Single source of truth for what counts as a landing instead of a crash, used by the physics,
+/// the speedometer and the assists.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct LandingRules {
+    /// Window used by every platform without its own rule, and while no platform is below
+    pub default_window: SpeedWindow,
+    /// Platform factor and its own window, none by default so every platform keeps the original rule
+    pub platform_windows: Vec<(i32, SpeedWindow)>,
+    /// Below this radar height a vertical velocity outside the window starts the flashing warning
+    pub warning_height: f32,
+}
+
+impl Default for LandingRules {
+    fn default() -> Self {
+        Self {
+            default_window: SpeedWindow::new(-35.0, 1.0),
+            platform_windows: vec![],
+            warning_height: 150.0,
+        }
+    }
+}
+
+impl LandingRules {
+    /// Default rules with every fastest allowed touchdown scaled by the difficulty.
+    pub fn for_difficulty(difficulty: Difficulty) -> Self {
+        let scale = difficulty.landing_speed_scale();
+        let mut rules = Self::default();
+        rules.default_window.min *= scale;
+        for (_, window) in rules.platform_windows.iter_mut() {
+            window.min *= scale;
+        }
+        rules
+    }
+
+    /// Vertical velocity window to touch down on the platform with `platform_factor`.
+    pub fn window(&self, platform_factor: Option<i32>) -> SpeedWindow {
+        platform_factor
+            .and_then(|factor| {
+                self.platform_windows
+                    .iter()
+                    .find(|(platform, _)| *platform == factor)
+                    .map(|(_, window)| *window)
+            })
+            .unwrap_or(self.default_window)
+    }
+
+    pub fn is_safe(&self, vertical_velocity: f32, platform_factor: Option<i32>) -> bool {
+        self.window(platform_factor).contains(vertical_velocity)
+    }
+
+    /// Whether the speedometer should flash, descending out of the window close to the ground.
+    pub fn is_warning(
+        &self,
+        vertical_velocity: f32,
+        platform_factor: Option<i32>,
+        height: Option<f32>,
+    ) -> bool {
+        height.is_some_and(|height| height < self.warning_height)
+            && !self.is_safe(vertical_velocity, platform_factor)
+    }
+}
+
+/// Open range of vertical velocities allowed at touchdown.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpeedWindow {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl SpeedWindow {
+    pub const fn new(min: f32, max: f32) -> Self {
+        Self { min, max }
+    }
+
+    pub fn contains(&self, vertical_velocity: f32) -> bool {
+        vertical_velocity > self.min && vertical_velocity < self.max
+    }
+}
+
+/// Speed bar offset of `vertical_velocity`, linear around zero and compressed for large speeds so
+/// the indicator never leaves the bar.
+pub fn speed_bar_offset(vertical_velocity: f32) -> f32 {
+    SPEED_BAR_HALF_HEIGHT * vertical_velocity / (vertical_velocity.abs() + SPEED_BAR_SCALE_KNEE)
+}
+
+#[test]
+fn landing_rules_test() {
+    let mut rules = LandingRules::default();
+    assert!(rules.is_safe(-30.0, None));
+    assert!(rules.is_safe(-30.0, Some(2)));
+    assert!(rules.is_safe(-30.0, Some(10)));
+    assert!(!rules.is_safe(1.0, None));
+    assert!(rules.is_warning(-50.0, None, Some(100.0)));
+    assert!(!rules.is_warning(-50.0, None, Some(400.0)));
+    rules.platform_windows = vec![(10, SpeedWindow::new(-25.0, 1.0))];
+    assert!(rules.is_safe(-30.0, Some(5)));
+    assert!(!rules.is_safe(-30.0, Some(10)));
+    assert!(!rules.is_warning(-10.0, Some(10), Some(100.0)));
+    assert_eq!(speed_bar_offset(0.0), 0.0);
+    assert_eq!(speed_bar_offset(-100.0), -SPEED_BAR_HALF_HEIGHT / 2.0);
+    assert!(speed_bar_offset(10_000.0) < SPEED_BAR_HALF_HEIGHT);
+}
@@ -0,0 +1,458 @@
+use bevy::prelude::*;
+use bevy_persistent::Persistent;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::asset_loader::UiAssets;
+use crate::attract::AttractMode;
+use crate::game::Scores;
+use crate::localization::Localization;
+use crate::menu::{
+    screen_entries, spawn_game_over_menu_handler, MenuEntryAction, MenuHandler, TextInput,
+    TextInputEvent, GREY_COLOR, PRIMARY_COLOR, SECONDARY_COLOR,
+};
+use crate::profile::{ProfileSelectedEvent, Profiles};
+use crate::session::GameSession;
+use crate::state::{AppState, GameState};
+use crate::storage::{load_persistent, Versioned};
+
+pub const LEADERBOARD_SIZE: usize = 10;
+const NAME_MAX_LENGTH: usize = 12;
+const LEADERBOARD_FILE_NAME: &str = "leaderboard.json";
+const LEGACY_SCORES_FILE_NAME: &str = "scores.json";
+/// Name of the entry imported from the legacy best score, which had no name.
+const LEGACY_ENTRY_NAME: &str = "???";
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, persist_leaderboard_system)
+            .add_systems(
+                PostUpdate,
+                persist_leaderboard_system.run_if(on_event::<ProfileSelectedEvent>()),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                spawn_name_entry_system.run_if(run_qualifies_for_leaderboard),
+            )
+            .add_systems(OnEnter(AppState::HallOfFame), spawn_hall_of_fame_system)
+            .add_systems(
+                Update,
+                name_entry_submit_system.run_if(on_event::<TextInputEvent>()),
+            );
+    }
+}
+
+// Conditions
+/// Runs flown by the attract mode demo or helped by the autopilot never qualify.
+pub fn run_qualifies_for_leaderboard(
+    scores: Res<Scores>,
+    leaderboard: Res<Persistent<Leaderboard>>,
+    attract_mode: Res<AttractMode>,
+    session: Option<Res<GameSession>>,
+) -> bool {
+    let autopilot_used = session.is_some_and(|session| session.autopilot_used);
+    !attract_mode.active && !autopilot_used && leaderboard.qualifies(scores.score)
+}
+
+// Systems
+fn persist_leaderboard_system(mut commands: Commands, profiles: Res<Persistent<Profiles>>) {
+    let dir = profiles.active_dir();
+    import_legacy_best_score(&dir);
+    commands.insert_resource(load_persistent::<Leaderboard>(
+        "leaderboard",
+        &dir,
+        LEADERBOARD_FILE_NAME,
+    ));
+}
+
+/// Before the leaderboard only the best score and gravity were kept, in `scores.json`. They become
+/// the first leaderboard entry, the old file is kept as a backup.
+fn import_legacy_best_score(dir: &Path) {
+    let legacy_path = dir.join(LEGACY_SCORES_FILE_NAME);
+    let path = dir.join(LEADERBOARD_FILE_NAME);
+    if path.exists() || !legacy_path.exists() {
+        return;
+    }
+    let legacy_scores = fs::read_to_string(&legacy_path)
+        .map_err(|error| error.to_string())
+        .and_then(|content| {
+            serde_json::from_str::<LegacyBestScore>(&content).map_err(|error| error.to_string())
+        });
+    let leaderboard = match legacy_scores {
+        Ok(legacy_scores) => Leaderboard::from(legacy_scores),
+        Err(error) => {
+            warn!("legacy best score could not be read ({}), skipped", error);
+            return;
+        }
+    };
+    let imported = serde_json::to_string(&leaderboard)
+        .map_err(|error| error.to_string())
+        .and_then(|json| fs::write(&path, json).map_err(|error| error.to_string()))
+        .and_then(|_| {
+            fs::rename(&legacy_path, legacy_path.with_extension("json.bak"))
+                .map_err(|error| error.to_string())
+        });
+    match imported {
+        Ok(()) => info!("legacy best score imported into the leaderboard"),
+        Err(error) => error!("failed to import the legacy best score: {}", error),
+    }
+}
+
+fn spawn_name_entry_system(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    localization: Res<Localization>,
+    leaderboard: Res<Persistent<Leaderboard>>,
+) {
+    let name_input = TextInput::new(leaderboard.last_name.clone(), NAME_MAX_LENGTH);
+    let text_style = |font_size: f32, color: Color| TextStyle {
+        font: assets.font_kenvector.clone(),
+        font_size,
+        color,
+    };
+    commands
+        .spawn((
+            StateScoped(GameState::GameOver),
+            NameEntryScreen,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(20.0),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                localization.text("toast.new_hi_score"),
+                text_style(80.0, SECONDARY_COLOR),
+            ));
+            parent.spawn(TextBundle::from_section(
+                localization.text("leaderboard.enter_name"),
+                text_style(30.0, GREY_COLOR),
+            ));
+            parent.spawn((
+                NameEntryText,
+                TextBundle::from_section(name_input.label(), text_style(50.0, Color::WHITE)),
+                name_input,
+            ));
+            parent.spawn(TextBundle::from_section(
+                localization.text("leaderboard.confirm_hint"),
+                text_style(20.0, GREY_COLOR),
+            ));
+        });
+}
+
+/// Enter records the run with the typed name, Escape leaves it out of the leaderboard.
+fn name_entry_submit_system(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    localization: Res<Localization>,
+    scores: Res<Scores>,
+    screen_query: Query<Entity, With<NameEntryScreen>>,
+    name_text_query: Query<(), With<NameEntryText>>,
+    mut text_input_events: EventReader<TextInputEvent>,
+    mut leaderboard: ResMut<Persistent<Leaderboard>>,
+) {
+    let Some(event) = text_input_events
+        .read()
+        .find(|event| name_text_query.contains(event.entity))
+    else {
+        return;
+    };
+    if event.submitted {
+        let name = match event.value.as_str() {
+            "" => localization.text("leaderboard.player").to_string(),
+            name => name.to_string(),
+        };
+        let entry = LeaderboardEntry::new(name, &scores);
+        if let Err(error) = leaderboard.update(|leaderboard| leaderboard.insert(entry.clone())) {
+            error!("failed to update leaderboard: {}", error);
+        }
+    }
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    spawn_game_over_menu_handler(&mut commands, &assets, &localization);
+}
+
+fn spawn_hall_of_fame_system(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    localization: Res<Localization>,
+    leaderboard: Res<Persistent<Leaderboard>>,
+) {
+    let entity = MenuHandler {
+        main_text: "".into(),
+        main_text_color: PRIMARY_COLOR,
+        main_text_blink: false,
+        selected_id: 0,
+        entries: screen_entries(&localization),
+        escape_action: Some(MenuEntryAction::Back),
+        ..default()
+    }
+    .spawn(&mut commands, assets.font_kenvector.clone());
+    commands
+        .entity(entity)
+        .insert(StateScoped(AppState::HallOfFame));
+    let text_style = |color: Color| TextStyle {
+        font: assets.font_vt323.clone(),
+        font_size: 30.0,
+        color,
+    };
+    commands
+        .spawn((
+            StateScoped(AppState::HallOfFame),
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(70.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    localization.text("menu.hall_of_fame"),
+                    TextStyle {
+                        font: assets.font_kenvector.clone(),
+                        font_size: 50.0,
+                        color: PRIMARY_COLOR,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                }),
+            );
+            parent.spawn(TextBundle::from_section(
+                leaderboard_row(
+                    "#",
+                    localization.text("leaderboard.name"),
+                    localization.text("hud.score"),
+                    localization.text("leaderboard.level"),
+                    localization.text("hud.gravity"),
+                    localization.text("leaderboard.date"),
+                ),
+                text_style(GREY_COLOR),
+            ));
+            if leaderboard.entries.is_empty() {
+                parent.spawn(TextBundle::from_section(
+                    localization.text("leaderboard.empty"),
+                    text_style(Color::WHITE),
+                ));
+            }
+            for (index, entry) in leaderboard.entries.iter().enumerate() {
+                parent.spawn(TextBundle::from_section(
+                    leaderboard_row(
+                        &(index + 1).to_string(),
+                        &entry.name,
+                        &entry.score.to_string(),
+                        &entry.level.to_string(),
+                        &format!("x{:.1}", entry.gravity),
+                        &entry.formatted_date(),
+                    ),
+                    text_style(Color::WHITE),
+                ));
+            }
+        });
+}
+
+/// Table row padded for the monospaced VT323 font.
+fn leaderboard_row(
+    rank: &str,
+    name: &str,
+    score: &str,
+    level: &str,
+    gravity: &str,
+    date: &str,
+) -> String {
+    format!(
+        "{:>2}  {:<12}  {:>6}  {:>5}  {:>7}  {:>10}",
+        rank, name, score, level, gravity, date
+    )
+}
+
+/// `YYYY-MM-DD` of a timestamp in seconds since the Unix epoch, in UTC.
+fn format_date(seconds: u64) -> String {
+    // days to civil date, from Howard Hinnant's chrono-compatible algorithms
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Resources
+/// Best runs, highest score first.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Leaderboard {
+    #[serde(default)]
+    pub version: u32,
+    pub entries: Vec<LeaderboardEntry>,
+    /// Offered again on the next name entry
+    pub last_name: String,
+}
+
+impl Default for Leaderboard {
+    fn default() -> Self {
+        Self {
+            version: Self::VERSION,
+            entries: vec![],
+            last_name: "".into(),
+        }
+    }
+}
+
+impl Versioned for Leaderboard {
+    const VERSION: u32 = 1;
+}
+
+impl From<LegacyBestScore> for Leaderboard {
+    fn from(legacy_best_score: LegacyBestScore) -> Self {
+        let mut leaderboard = Leaderboard::default();
+        // the gravity goes up by 0.1 with every landing
+        let landings = ((legacy_best_score.gravity - 1.0) * 10.0).round().max(0.0) as u32;
+        if legacy_best_score.hi_score > 0 {
+            leaderboard.entries.push(LeaderboardEntry {
+                name: LEGACY_ENTRY_NAME.into(),
+                score: legacy_best_score.hi_score,
+                gravity: legacy_best_score.gravity,
+                landings,
+                level: landings + 1,
+                date: 0,
+            });
+        }
+        leaderboard
+    }
+}
+
+impl Leaderboard {
+    pub fn best_score(&self) -> i32 {
+        self.entries.first().map_or(0, |entry| entry.score)
+    }
+
+    pub fn qualifies(&self, score: i32) -> bool {
+        score > 0
+            && (self.entries.len() < LEADERBOARD_SIZE
+                || self.entries.last().is_some_and(|entry| score > entry.score))
+    }
+
+    /// Inserts `entry` after the entries with the same score, dropping the ones beyond the table.
+    pub fn insert(&mut self, entry: LeaderboardEntry) {
+        let position = self
+            .entries
+            .iter()
+            .position(|other| entry.score > other.score)
+            .unwrap_or(self.entries.len());
+        self.last_name = entry.name.clone();
+        self.entries.insert(position, entry);
+        self.entries.truncate(LEADERBOARD_SIZE);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub score: i32,
+    /// Highest gravity reached, the last one of the run
+    pub gravity: f32,
+    pub landings: u32,
+    pub level: u32,
+    /// Seconds since the Unix epoch
+    pub date: u64,
+}
+
+impl LeaderboardEntry {
+    pub fn new(name: String, scores: &Scores) -> Self {
+        Self {
+            name,
+            score: scores.score,
+            gravity: scores.gravity,
+            landings: scores.level - 1,
+            level: scores.level,
+            date: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs()),
+        }
+    }
+
+    /// Date of the run, unknown for the entry imported from the legacy best score.
+    pub fn formatted_date(&self) -> String {
+        match self.date {
+            0 => "-".into(),
+            date => format_date(date),
+        }
+    }
+}
+
+/// Contents of the legacy `scores.json`.
+#[derive(Deserialize, Debug)]
+struct LegacyBestScore {
+    hi_score: i32,
+    gravity: f32,
+}
+
+// Components
+#[derive(Component, Debug)]
+struct NameEntryScreen;
+
+#[derive(Component, Debug)]
+struct NameEntryText;
+
+#[test]
+fn leaderboard_test() {
+    let entry = |name: &str, score: i32| LeaderboardEntry {
+        name: name.into(),
+        score,
+        gravity: 1.0,
+        landings: 0,
+        level: 1,
+        date: 0,
+    };
+    let mut leaderboard = Leaderboard::default();
+    assert!(!leaderboard.qualifies(0));
+    for score in 1..=LEADERBOARD_SIZE as i32 {
+        leaderboard.insert(entry("ADA", score * 100));
+    }
+    assert_eq!(leaderboard.best_score(), 1000);
+    assert!(!leaderboard.qualifies(100));
+    assert!(leaderboard.qualifies(150));
+    leaderboard.insert(entry("BOB", 1000));
+    assert_eq!(leaderboard.entries.len(), LEADERBOARD_SIZE);
+    assert_eq!(leaderboard.entries[1].name, "BOB");
+    assert_eq!(leaderboard.entries.last().unwrap().score, 200);
+    assert_eq!(leaderboard.last_name, "BOB");
+    let legacy_leaderboard = Leaderboard::from(LegacyBestScore {
+        hi_score: 3000,
+        gravity: 1.5,
+    });
+    assert_eq!(legacy_leaderboard.entries[0].landings, 5);
+    assert_eq!(legacy_leaderboard.entries[0].level, 6);
+    assert_eq!(format_date(0), "1970-01-01");
+    assert_eq!(format_date(1_729_296_000), "2024-10-19");
+}
@@ -0,0 +1,100 @@
+#![allow(
+    clippy::too_many_arguments,
+    clippy::type_complexity,
+    clippy::single_match,
+    clippy::collapsible_else_if,
+    non_upper_case_globals
+)]
+
+pub mod accessibility;
+pub mod asset_loader;
+pub mod assists;
+pub mod attract;
+pub mod audio;
+pub mod autopilot;
+pub mod benchmark;
+pub mod camera;
+pub mod collider;
+pub mod control_server;
+pub mod controls;
+pub mod debug;
+pub mod display;
+pub mod environment;
+pub mod explosion;
+pub mod fuel;
+pub mod game;
+pub mod gizmos;
+pub mod hud;
+pub mod input_buffer;
+pub mod landing_rules;
+pub mod leaderboard;
+pub mod localization;
+pub mod menu;
+pub mod movement;
+pub mod notification;
+pub mod particles_thruster;
+pub mod pointer;
+pub mod profile;
+pub mod radar;
+pub mod save;
+pub mod session;
+pub mod settings;
+pub mod spaceship;
+pub mod speedometer;
+pub mod state;
+pub mod statistics;
+pub mod storage;
+pub mod trajectory;
+
+use bevy::prelude::*;
+use svg::node::element::path::{Command, Data};
+use svg::node::element::tag::Path;
+use svg::parser::Event;
+
+use game::WorldBoundsVertices2D;
+
+pub const MAIN_TITLE: &str = "Rusty Lander";
+pub const WINDOW_WIDTH: f32 = 1024.0;
+pub const WINDOW_HEIGHT: f32 = 720.0;
+/// HUD black bars along the right and bottom edges of the play area.
+pub const HUD_RIGHT_BAR_WIDTH: f32 = 54.0;
+pub const HUD_BOTTOM_BAR_HEIGHT: f32 = 60.0;
+pub const GRAVITY: f32 = 58.0;
+pub const PHYSICS_LENGTH_UNIT: f32 = 20.0;
+
+pub fn get_world_bounds_resource_2d() -> WorldBoundsVertices2D {
+    let mut current_point: Vec2 = Vec2::new(0.0, 0.0);
+    let mut world_bounds_resource_2d = WorldBoundsVertices2D { data: vec![] };
+    let path = "assets/svg/landscape.svg";
+    let mut content = String::new();
+    for event in svg::open(path, &mut content).unwrap() {
+        match event {
+            Event::Tag(Path, _, attributes) => {
+                let data = attributes.get("d").unwrap();
+                let data = Data::parse(data).unwrap();
+                for command in data.iter() {
+                    match command {
+                        Command::Move(_position, params) => {
+                            current_point.x = params[0];
+                            current_point.y = params[1];
+                            world_bounds_resource_2d.data.push(current_point);
+                        }
+                        Command::CubicCurve(_position, params) => {
+                            let chunks = params.chunks_exact(2);
+                            for chunk in chunks {
+                                let mut next_point = current_point;
+                                next_point.x += chunk[0];
+                                next_point.y -= chunk[1];
+                                world_bounds_resource_2d.data.push(next_point);
+                                current_point = next_point;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    world_bounds_resource_2d
+}
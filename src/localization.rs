@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use bevy_persistent::Persistent;
+use std::collections::HashMap;
+
+use crate::settings::{Language, Settings};
+
+// String tables, flat JSON objects from keys to texts. Both VT323 and Fira Sans cover the
+// Latin-1 glyphs they need.
+const ENGLISH_TABLE: &str = include_str!("../assets/locales/en.json");
+const CATALAN_TABLE: &str = include_str!("../assets/locales/ca.json");
+const SPANISH_TABLE: &str = include_str!("../assets/locales/es.json");
+
+pub struct LocalizationPlugin;
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, init_localization_system)
+            .add_systems(
+                Update,
+                (
+                    switch_language_system,
+                    update_localized_texts_system.run_if(resource_changed::<Localization>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+// Systems
+fn init_localization_system(mut commands: Commands, settings: Res<Persistent<Settings>>) {
+    commands.insert_resource(Localization::new(settings.language));
+}
+
+fn switch_language_system(
+    settings: Res<Persistent<Settings>>,
+    mut localization: ResMut<Localization>,
+) {
+    if settings.language != localization.language {
+        *localization = Localization::new(settings.language);
+        info!("Language switched to {}", settings.language.name());
+    }
+}
+
+fn update_localized_texts_system(
+    localization: Res<Localization>,
+    mut texts_query: Query<(&LocalizedText, &mut Text)>,
+) {
+    for (localized_text, mut text) in texts_query.iter_mut() {
+        text.sections[0].value = localization.text(localized_text.0).into();
+    }
+}
+
+// Resources
+/// Texts of the selected language, falling back to English and then to the key itself.
+#[derive(Resource, Debug, Clone)]
+pub struct Localization {
+    language: Language,
+    table: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl Localization {
+    pub fn new(language: Language) -> Self {
+        Self {
+            language,
+            table: parse_table(language),
+            fallback: parse_table(Language::English),
+        }
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    pub fn text<'a>(&'a self, key: &'a str) -> &'a str {
+        self.table
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    /// Text with its `{0}`, `{1}`... placeholders replaced by `arguments`.
+    pub fn format(&self, key: &str, arguments: &[&str]) -> String {
+        arguments
+            .iter()
+            .enumerate()
+            .fold(self.text(key).to_string(), |text, (index, argument)| {
+                text.replace(&format!("{{{}}}", index), argument)
+            })
+    }
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        Self::new(Language::English)
+    }
+}
+
+fn parse_table(language: Language) -> HashMap<String, String> {
+    let table = match language {
+        Language::English => ENGLISH_TABLE,
+        Language::Catalan => CATALAN_TABLE,
+        Language::Spanish => SPANISH_TABLE,
+    };
+    serde_json::from_str(table).expect("invalid localization table")
+}
+
+// Components
+/// Text entity showing the localized text of a key, updated when the language changes.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct LocalizedText(pub &'static str);
+
+#[test]
+fn localization_tables_test() {
+    let english = parse_table(Language::English);
+    for language in [Language::Catalan, Language::Spanish] {
+        let table = parse_table(language);
+        for (key, text) in english.iter() {
+            let translation = table
+                .get(key)
+                .unwrap_or_else(|| panic!("{} missing in {:?}", key, language));
+            for placeholder in ["{0}", "{1}"] {
+                assert_eq!(
+                    text.contains(placeholder),
+                    translation.contains(placeholder)
+                );
+            }
+        }
+        assert_eq!(table.len(), english.len());
+    }
+    let localization = Localization::new(Language::Catalan);
+    assert_eq!(localization.text("menu.play"), "Jugar");
+    assert_eq!(localization.text("missing.key"), "missing.key");
+    assert_eq!(
+        localization.format("controls.bound", &["Pausa", "P"]),
+        "Pausa assignat a P"
+    );
+}
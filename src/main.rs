@@ -1,55 +1,48 @@
-#![allow(
-    clippy::too_many_arguments,
-    clippy::type_complexity,
-    clippy::single_match,
-    clippy::collapsible_else_if,
-    non_upper_case_globals
-)]
-
-mod asset_loader;
-mod audio;
-mod camera;
-mod collider;
-mod debug;
-mod explosion;
-mod fuel;
-mod game;
-mod gizmos;
-mod menu;
-mod movement;
-mod particles_thruster;
-mod spaceship;
-mod speedometer;
-mod state;
-
 use avian2d::{math::*, prelude::*};
 use bevy::prelude::*;
-use bevy::window::WindowResolution;
+use bevy::window::{WindowResizeConstraints, WindowResolution};
 use leafwing_input_manager::plugin::InputManagerPlugin;
 use std::string::ToString;
-use svg::node::element::path::{Command, Data};
-use svg::node::element::tag::Path;
-use svg::parser::Event;
-
-use asset_loader::AssetsLoaderPlugin;
-use audio::AudioPlugin;
-use camera::CameraPlugin;
-use collider::ColliderPlugin;
-use debug::DebugPlugin;
-use explosion::ExplosionPlugin;
-use fuel::FuelPlugin;
-use game::{GamePlugin, InGameSet, WorldBoundsVertices2D};
-use gizmos::GizmosPlugin;
-use menu::{MenuAction, MenuPlugin};
-use movement::CharacterControllerPlugin;
-use particles_thruster::ParticlesThrusterPlugin;
-use spaceship::SpaceshipPlugin;
-use speedometer::SpeedometerPlugin;
-use state::StatesPlugin;
 
-const MAIN_TITLE: &str = "Rusty Lander";
-const WINDOW_WIDTH: f32 = 1024.0;
-const WINDOW_HEIGHT: f32 = 720.0;
+use rusty_lander::accessibility::AccessibilityPlugin;
+use rusty_lander::asset_loader::AssetsLoaderPlugin;
+use rusty_lander::assists::AssistsPlugin;
+use rusty_lander::attract::AttractModePlugin;
+use rusty_lander::audio::AudioPlugin;
+use rusty_lander::autopilot::AutopilotPlugin;
+use rusty_lander::camera::CameraPlugin;
+use rusty_lander::collider::ColliderPlugin;
+use rusty_lander::control_server::ControlServerPlugin;
+use rusty_lander::controls::ControlsPlugin;
+use rusty_lander::debug::DebugPlugin;
+use rusty_lander::display::DisplayPlugin;
+use rusty_lander::explosion::ExplosionPlugin;
+use rusty_lander::fuel::FuelPlugin;
+use rusty_lander::game::GamePlugin;
+use rusty_lander::gizmos::GizmosPlugin;
+use rusty_lander::hud::HudPlugin;
+use rusty_lander::input_buffer::InputBufferPlugin;
+use rusty_lander::leaderboard::LeaderboardPlugin;
+use rusty_lander::localization::LocalizationPlugin;
+use rusty_lander::menu::{MenuAction, MenuPlugin};
+use rusty_lander::movement::CharacterControllerPlugin;
+use rusty_lander::notification::NotificationPlugin;
+use rusty_lander::particles_thruster::ParticlesThrusterPlugin;
+use rusty_lander::pointer::PointerControlsPlugin;
+use rusty_lander::profile::ProfilePlugin;
+use rusty_lander::radar::RadarPlugin;
+use rusty_lander::save::SavePlugin;
+use rusty_lander::session::SessionPlugin;
+use rusty_lander::settings::SettingsPlugin;
+use rusty_lander::spaceship::SpaceshipPlugin;
+use rusty_lander::speedometer::SpeedometerPlugin;
+use rusty_lander::state::StatesPlugin;
+use rusty_lander::statistics::StatisticsPlugin;
+use rusty_lander::trajectory::TrajectoryPlugin;
+use rusty_lander::{
+    get_world_bounds_resource_2d, GRAVITY, MAIN_TITLE, PHYSICS_LENGTH_UNIT, WINDOW_HEIGHT,
+    WINDOW_WIDTH,
+};
 
 fn main() {
     let mut app = App::new();
@@ -60,40 +53,55 @@ fn main() {
                 primary_window: Some(Window {
                     title: MAIN_TITLE.to_string(),
                     resolution: WindowResolution::new(WINDOW_WIDTH, WINDOW_HEIGHT),
-                    resizable: false,
+                    resizable: true,
+                    resize_constraints: WindowResizeConstraints {
+                        min_width: WINDOW_WIDTH / 2.0,
+                        min_height: WINDOW_HEIGHT / 2.0,
+                        ..default()
+                    },
                     focused: true,
                     ..default()
                 }),
                 ..default()
             })
             .set(ImagePlugin::default_nearest()),
-        PhysicsPlugins::default().with_length_unit(20.0),
+        PhysicsPlugins::default().with_length_unit(PHYSICS_LENGTH_UNIT),
         InputManagerPlugin::<MenuAction>::default(),
     ));
     // Enable Avian2d debug renders when compiled in debug mode
     #[cfg(debug_assertions)]
     app.add_plugins(PhysicsDebugPlugin::default());
     // Resources
-    app.insert_resource(Gravity(Vector::NEG_Y * 58.0))
+    app.insert_resource(Gravity(Vector::NEG_Y * GRAVITY))
         .insert_resource(get_world_bounds_resource_2d());
-    // System ordering
-    app.configure_sets(
-        FixedUpdate,
-        (
-            InGameSet::Collisions,
-            InGameSet::Physics,
-            InGameSet::SpeedBar,
-        )
-            .chain(),
-    );
     // Custom plugins
     #[cfg(debug_assertions)]
     app.add_plugins(GizmosPlugin);
     app.add_plugins(StatesPlugin)
         .add_plugins(MenuPlugin)
+        .add_plugins(ProfilePlugin)
+        .add_plugins(ControlsPlugin)
+        .add_plugins(SettingsPlugin)
+        .add_plugins(LocalizationPlugin)
+        .add_plugins(AccessibilityPlugin)
+        .add_plugins(NotificationPlugin)
+        .add_plugins(SavePlugin)
+        .add_plugins(SessionPlugin)
+        .add_plugins(LeaderboardPlugin)
+        .add_plugins(StatisticsPlugin)
+        .add_plugins(PointerControlsPlugin)
+        .add_plugins(InputBufferPlugin)
+        .add_plugins(AutopilotPlugin)
+        .add_plugins(AssistsPlugin)
+        .add_plugins(TrajectoryPlugin)
+        .add_plugins(RadarPlugin)
+        .add_plugins(HudPlugin)
+        .add_plugins(AttractModePlugin)
+        .add_plugins(ControlServerPlugin)
         .add_plugins(AssetsLoaderPlugin)
         .add_plugins(AudioPlugin)
         .add_plugins(CameraPlugin)
+        .add_plugins(DisplayPlugin)
         .add_plugins(DebugPlugin)
         .add_plugins(FuelPlugin)
         .add_plugins(ParticlesThrusterPlugin)
@@ -105,40 +113,3 @@ fn main() {
         .add_plugins(ExplosionPlugin)
         .run();
 }
-
-fn get_world_bounds_resource_2d() -> WorldBoundsVertices2D {
-    let mut current_point: Vec2 = Vec2::new(0.0, 0.0);
-    let mut world_bounds_resource_2d = WorldBoundsVertices2D { data: vec![] };
-    let path = "assets/svg/landscape.svg";
-    let mut content = String::new();
-    for event in svg::open(path, &mut content).unwrap() {
-        match event {
-            Event::Tag(Path, _, attributes) => {
-                let data = attributes.get("d").unwrap();
-                let data = Data::parse(data).unwrap();
-                for command in data.iter() {
-                    match command {
-                        Command::Move(_position, params) => {
-                            current_point.x = params[0];
-                            current_point.y = params[1];
-                            world_bounds_resource_2d.data.push(current_point);
-                        }
-                        Command::CubicCurve(_position, params) => {
-                            let chunks = params.chunks_exact(2);
-                            for chunk in chunks {
-                                let mut next_point = current_point;
-                                next_point.x += chunk[0];
-                                next_point.y -= chunk[1];
-                                world_bounds_resource_2d.data.push(next_point);
-                                current_point = next_point;
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            }
-            _ => {}
-        }
-    }
-    world_bounds_resource_2d
-}
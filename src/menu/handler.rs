@@ -1,126 +1,233 @@
 use bevy::prelude::*;
+use bevy_persistent::Persistent;
 use leafwing_input_manager::prelude::*;
 
 use crate::menu::{MenuAction, DARK_GREY_COLOR, GREY_COLOR};
+use crate::settings::Settings;
+use crate::state::AppState;
 
 #[derive(Component)]
 pub struct DrawBlinkTimer(pub Timer);
 
 #[derive(Component)]
-pub struct ButtonId(i32);
+pub struct ButtonId(pub i32);
 
-#[derive(Component)]
+/// What a menu entry does when accepted.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MenuEntryAction {
+    StartGame,
+    /// Opens another screen, Back comes back to the current one. Opening the main menu forgets
+    /// every screen opened before.
+    OpenScreen(AppState),
+    /// Leaves the pause menu back to the flight
+    Resume,
+    Quit,
+    /// Replaces the menu entries in place, Back restores the parent menu
+    Submenu(Box<MenuHandler>),
+    /// Closes the open submenu, or goes back to the screen that opened the current one
+    Back,
+    /// Sends a `MenuCustomEvent` with this id, for screens that handle their own entries
+    Custom(&'static str),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MenuEntry {
+    pub label: String,
+    pub action: MenuEntryAction,
+    /// Disabled entries are greyed out and skipped by the selection
+    pub enabled: bool,
+}
+
+impl MenuEntry {
+    pub fn new(label: impl Into<String>, action: MenuEntryAction) -> Self {
+        Self {
+            label: label.into(),
+            action,
+            enabled: true,
+        }
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+/// Menu declared as data, spawned as a title over a column of buttons.
+#[derive(Component, Clone, Debug, PartialEq, Default)]
 pub struct MenuHandler {
     pub main_text: String,
     pub main_text_color: Color,
     pub main_text_blink: bool,
-    pub entries: Vec<String>,
+    pub entries: Vec<MenuEntry>,
     pub selected_id: i32,
+    /// Run by Escape once every submenu is closed, None lets Escape through
+    pub escape_action: Option<MenuEntryAction>,
+    /// Menus the open submenu was opened from, the innermost last
+    pub parents: Vec<MenuHandler>,
 }
+
 impl MenuHandler {
     const SELECTED_BORDER: Color = GREY_COLOR;
     const SELECTED_BG: Color = DARK_GREY_COLOR;
     const UNSELECTED_BORDER: Color = DARK_GREY_COLOR;
     const UNSELECTED_BG: Color = Color::BLACK;
+    const HIGH_CONTRAST_SELECTED_BORDER: Color = Color::WHITE;
+    const HIGH_CONTRAST_UNSELECTED_BORDER: Color = Color::BLACK;
 
-    pub fn spawn(self, commands: &mut Commands, font: Handle<Font>) -> Entity {
+    pub fn spawn(mut self, commands: &mut Commands, font: Handle<Font>) -> Entity {
+        self.selected_id = self.enabled_entry_from(self.selected_id, 1);
+        commands
+            .spawn((NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::SpaceEvenly,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },))
+            .with_children(|parent| self.spawn_contents(parent, font))
+            .insert(self)
+            .id()
+    }
+
+    /// Replaces the title and buttons of the spawned menu `entity`, after opening or closing a submenu.
+    pub fn respawn_contents(&self, commands: &mut Commands, entity: Entity, font: Handle<Font>) {
+        commands
+            .entity(entity)
+            .despawn_descendants()
+            .with_children(|parent| self.spawn_contents(parent, font));
+    }
+
+    fn spawn_contents(&self, parent: &mut ChildBuilder, font: Handle<Font>) {
         let button_style = Style {
-            width: Val::Px(150.0),
-            height: Val::Px(45.0),
+            width: Val::Px(190.0),
+            height: Val::Px(42.0),
             border: UiRect::all(Val::Px(5.0)),
             justify_content: JustifyContent::Center,
             align_items: AlignItems::Center,
-            margin: UiRect::all(Val::Px(5.0)),
+            margin: UiRect::all(Val::Px(3.0)),
             ..default()
         };
-        let entity = commands
+        parent
             .spawn((NodeBundle {
                 style: Style {
-                    width: Val::Percent(100.0),
-                    height: Val::Percent(100.0),
+                    height: Val::Percent(35.0),
                     align_items: AlignItems::Center,
-                    justify_content: JustifyContent::SpaceEvenly,
-                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
                     ..default()
                 },
                 ..default()
             },))
             .with_children(|parent| {
-                parent
-                    .spawn((NodeBundle {
-                        style: Style {
-                            height: Val::Percent(50.0),
-                            align_items: AlignItems::Center,
-                            justify_content: JustifyContent::Center,
-                            ..default()
+                let mut text = parent.spawn((TextBundle {
+                    style: Style { ..default() },
+                    text: Text::from_section(
+                        self.main_text.clone(),
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 120.0,
+                            color: self.main_text_color,
                         },
-                        ..default()
-                    },))
-                    .with_children(|parent| {
-                        let mut text = parent.spawn((TextBundle {
-                            style: Style { ..default() },
-                            text: Text::from_section(
-                                self.main_text.clone(),
+                    ),
+                    ..default()
+                },));
+                if self.main_text_blink {
+                    text.insert(DrawBlinkTimer(Timer::from_seconds(
+                        0.5,
+                        TimerMode::Repeating,
+                    )));
+                }
+            });
+        parent
+            .spawn((NodeBundle {
+                style: Style {
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },))
+            .with_children(|parent| {
+                for (i, entry) in self.entries.iter().enumerate() {
+                    parent
+                        .spawn((
+                            ButtonBundle {
+                                style: button_style.clone(),
+                                border_radius: BorderRadius::all(Val::Px(10.0)),
+                                ..default()
+                            },
+                            ButtonId(i as i32),
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                entry.label.clone(),
                                 TextStyle {
                                     font: font.clone(),
-                                    font_size: 120.0,
-                                    color: self.main_text_color,
-                                },
-                            ),
-                            ..default()
-                        },));
-                        if self.main_text_blink {
-                            text.insert(DrawBlinkTimer(Timer::from_seconds(
-                                0.5,
-                                TimerMode::Repeating,
-                            )));
-                        }
-                    });
-                parent
-                    .spawn((NodeBundle {
-                        style: Style {
-                            align_items: AlignItems::Center,
-                            justify_content: JustifyContent::Center,
-                            flex_direction: FlexDirection::Column,
-                            ..default()
-                        },
-                        ..default()
-                    },))
-                    .with_children(|parent| {
-                        for (i, entry) in self.entries.iter().enumerate() {
-                            parent
-                                .spawn((
-                                    ButtonBundle {
-                                        style: button_style.clone(),
-                                        border_radius: BorderRadius::all(Val::Px(10.0)),
-                                        ..default()
+                                    font_size: 25.0,
+                                    color: if entry.enabled {
+                                        self.main_text_color
+                                    } else {
+                                        DARK_GREY_COLOR
                                     },
-                                    ButtonId(i as i32),
-                                ))
-                                .with_children(|parent| {
-                                    parent.spawn(TextBundle::from_section(
-                                        entry,
-                                        TextStyle {
-                                            font: font.clone(),
-                                            font_size: 25.0,
-                                            color: self.main_text_color,
-                                        },
-                                    ));
-                                });
-                        }
-                    });
-            })
-            .insert(self)
-            .id();
-        entity
+                                },
+                            ));
+                        });
+                }
+            });
+    }
+
+    pub fn selected_entry(&self) -> Option<&MenuEntry> {
+        self.entries
+            .get(self.selected_id as usize)
+            .filter(|entry| entry.enabled)
+    }
+
+    /// First enabled entry walking from `index` in `step` direction, wrapping around.
+    fn enabled_entry_from(&self, index: i32, step: i32) -> i32 {
+        let len = self.entries.len() as i32;
+        (0..len)
+            .map(|offset| (index + offset * step).rem_euclid(len))
+            .find(|candidate| self.entries[*candidate as usize].enabled)
+            .unwrap_or(index)
+    }
+
+    pub fn open_submenu(&mut self, submenu: MenuHandler) {
+        let mut parent = std::mem::replace(self, submenu);
+        self.selected_id = self.enabled_entry_from(self.selected_id, 1);
+        self.parents = std::mem::take(&mut parent.parents);
+        self.parents.push(parent);
+    }
+
+    /// Restores the parent menu, false when no submenu is open.
+    pub fn close_submenu(&mut self) -> bool {
+        let mut parents = std::mem::take(&mut self.parents);
+        let Some(parent) = parents.pop() else {
+            return false;
+        };
+        *self = parent;
+        self.parents = parents;
+        true
     }
 }
+
 pub fn menu_blink_system(
     mut commands: Commands,
     time: Res<Time>,
+    settings: Res<Persistent<Settings>>,
     mut query: Query<(Entity, &mut DrawBlinkTimer, &ViewVisibility)>,
 ) {
     for (entity, mut timer, visibility) in query.iter_mut() {
+        if !settings.accessibility.blinking {
+            if !visibility.get() {
+                commands.entity(entity).insert(Visibility::Visible);
+            }
+            continue;
+        }
         timer.0.tick(time.delta());
         if timer.0.finished() {
             let new_visibility = if visibility.get() {
@@ -136,25 +243,97 @@ pub fn menu_blink_system(
 pub fn menu_selection_system(
     mut menu: Query<Mut<MenuHandler>>,
     menu_action_state: Res<ActionState<MenuAction>>,
+    settings: Res<Persistent<Settings>>,
     mut buttons: Query<(&ButtonId, &mut BorderColor, &mut BackgroundColor)>,
 ) {
     if let Ok(mut menu) = menu.get_single_mut() {
         if menu_action_state.just_pressed(&MenuAction::MenuUp) {
-            menu.selected_id = (menu.selected_id - 1).rem_euclid(menu.entries.len() as i32);
+            menu.selected_id = menu.enabled_entry_from(menu.selected_id - 1, -1);
         }
         if menu_action_state.just_pressed(&MenuAction::MenuDown) {
-            menu.selected_id = (menu.selected_id + 1).rem_euclid(menu.entries.len() as i32);
+            menu.selected_id = menu.enabled_entry_from(menu.selected_id + 1, 1);
         }
-        if menu.is_changed() {
+        if menu.is_changed() || settings.is_changed() {
+            let (selected_border, unselected_border) = if settings.accessibility.high_contrast {
+                (
+                    MenuHandler::HIGH_CONTRAST_SELECTED_BORDER,
+                    MenuHandler::HIGH_CONTRAST_UNSELECTED_BORDER,
+                )
+            } else {
+                (MenuHandler::SELECTED_BORDER, MenuHandler::UNSELECTED_BORDER)
+            };
             for (button_id, mut border_color, mut bg_color) in buttons.iter_mut() {
                 if button_id.0 == menu.selected_id {
-                    border_color.0 = MenuHandler::SELECTED_BORDER;
+                    border_color.0 = selected_border;
                     bg_color.0 = MenuHandler::SELECTED_BG;
                 } else {
-                    border_color.0 = MenuHandler::UNSELECTED_BORDER;
+                    border_color.0 = unselected_border;
                     bg_color.0 = MenuHandler::UNSELECTED_BG;
                 }
             }
         }
     }
 }
+
+pub fn menu_pointer_system(
+    mut menu: Query<Mut<MenuHandler>>,
+    interaction_query: Query<(&Interaction, &ButtonId), Changed<Interaction>>,
+    mut menu_action_state: ResMut<ActionState<MenuAction>>,
+) {
+    let Ok(mut menu) = menu.get_single_mut() else {
+        return;
+    };
+    for (interaction, button_id) in interaction_query.iter() {
+        let enabled = menu
+            .entries
+            .get(button_id.0 as usize)
+            .is_some_and(|entry| entry.enabled);
+        if !enabled {
+            continue;
+        }
+        match *interaction {
+            Interaction::Hovered => {
+                if menu.selected_id != button_id.0 {
+                    menu.selected_id = button_id.0;
+                }
+            }
+            Interaction::Pressed => {
+                menu.selected_id = button_id.0;
+                menu_action_state.press(&MenuAction::Accept);
+            }
+            Interaction::None => {}
+        }
+    }
+}
+
+#[test]
+fn menu_navigation_test() {
+    let mut menu = MenuHandler {
+        entries: vec![
+            MenuEntry::new("Continue", MenuEntryAction::Resume).enabled(false),
+            MenuEntry::new("Play", MenuEntryAction::StartGame),
+            MenuEntry::new("Exit", MenuEntryAction::Quit),
+        ],
+        ..default()
+    };
+    assert_eq!(menu.enabled_entry_from(0, 1), 1);
+    assert_eq!(menu.enabled_entry_from(0, -1), 2);
+    assert_eq!(menu.selected_entry(), None);
+    let submenu = MenuHandler {
+        main_text: "Help".into(),
+        entries: vec![MenuEntry::new("Back", MenuEntryAction::Back)],
+        ..default()
+    };
+    menu.selected_id = 1;
+    menu.open_submenu(submenu.clone());
+    menu.open_submenu(submenu);
+    assert_eq!(menu.main_text, "Help");
+    assert_eq!(menu.parents.len(), 2);
+    assert!(menu.close_submenu());
+    assert!(menu.close_submenu());
+    assert!(!menu.close_submenu());
+    assert_eq!(
+        menu.selected_entry().map(|entry| &entry.action),
+        Some(&MenuEntryAction::StartGame)
+    );
+}
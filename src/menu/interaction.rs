@@ -1,57 +1,131 @@
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
 
-use crate::menu::{MenuAction, MenuHandler};
+use crate::asset_loader::UiAssets;
+use crate::menu::{MenuAction, MenuEntryAction, MenuHandler};
 use crate::state::{AppState, GameState};
 
-pub fn main_menu_input_system(
-    app_state: ResMut<State<AppState>>,
-    menu_action_state: Res<ActionState<MenuAction>>,
-    menu: Query<&MenuHandler>,
-    mut next_app_state: ResMut<NextState<AppState>>,
-    mut app_exit_events: EventWriter<AppExit>,
-) {
-    if let Ok(menu) = menu.get_single() {
-        if menu_action_state.just_pressed(&MenuAction::Accept) {
-            if app_state.get() == &AppState::Menu {
-                match menu.selected_id {
-                    0 => {
-                        next_app_state.set(AppState::Game);
-                    }
-                    1 => {
-                        next_app_state.set(AppState::Instructions);
-                    }
-                    2 => {
-                        next_app_state.set(AppState::Credits);
-                    }
-                    _ => {
-                        app_exit_events.send(AppExit::Success);
-                    }
-                }
+/// Sent by `MenuEntryAction::Custom` entries.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MenuCustomEvent(pub &'static str);
+
+/// Screens opened from other screens, the last one is where Back returns.
+#[derive(Resource, Debug, Default)]
+pub struct ScreenBackStack(pub Vec<AppState>);
+
+/// Everything needed to run any menu entry action.
+#[derive(SystemParam)]
+pub struct MenuActionRunner<'w, 's> {
+    commands: Commands<'w, 's>,
+    assets: Res<'w, UiAssets>,
+    app_state: Res<'w, State<AppState>>,
+    back_stack: ResMut<'w, ScreenBackStack>,
+    next_app_state: ResMut<'w, NextState<AppState>>,
+    next_game_state: ResMut<'w, NextState<GameState>>,
+    app_exit_events: EventWriter<'w, AppExit>,
+    custom_events: EventWriter<'w, MenuCustomEvent>,
+}
+
+impl MenuActionRunner<'_, '_> {
+    pub fn run(&mut self, entity: Entity, menu: &mut MenuHandler, action: MenuEntryAction) {
+        match action {
+            MenuEntryAction::StartGame => {
+                self.back_stack.0.clear();
+                self.next_app_state.set(AppState::Game);
+            }
+            MenuEntryAction::OpenScreen(AppState::Menu) => {
+                self.back_stack.0.clear();
+                self.next_app_state.set(AppState::Menu);
             }
-            if app_state.get() == &AppState::Instructions || app_state.get() == &AppState::Credits {
-                match menu.selected_id {
-                    0 => {
-                        next_app_state.set(AppState::Menu);
-                    }
-                    _ => {
-                        app_exit_events.send(AppExit::Success);
-                    }
+            MenuEntryAction::OpenScreen(app_state) => {
+                self.back_stack.0.push(*self.app_state.get());
+                self.next_app_state.set(app_state);
+            }
+            MenuEntryAction::Resume => {
+                self.next_game_state.set(GameState::Landing);
+            }
+            MenuEntryAction::Quit => {
+                self.app_exit_events.send(AppExit::Success);
+            }
+            MenuEntryAction::Submenu(submenu) => {
+                menu.open_submenu(*submenu);
+                menu.respawn_contents(
+                    &mut self.commands,
+                    entity,
+                    self.assets.font_kenvector.clone(),
+                );
+            }
+            MenuEntryAction::Back => {
+                if menu.close_submenu() {
+                    menu.respawn_contents(
+                        &mut self.commands,
+                        entity,
+                        self.assets.font_kenvector.clone(),
+                    );
+                } else if let Some(app_state) = self.back_stack.0.pop() {
+                    self.next_app_state.set(app_state);
                 }
             }
+            MenuEntryAction::Custom(id) => {
+                self.custom_events.send(MenuCustomEvent(id));
+            }
         }
     }
 }
 
-pub fn game_menu_input_system(
-    game_state: ResMut<State<GameState>>,
-    mut next_app_state: ResMut<NextState<AppState>>,
-    mut next_game_state: ResMut<NextState<GameState>>,
+pub fn menu_accept_system(
+    menu_action_state: Res<ActionState<MenuAction>>,
+    mut menu_query: Query<(Entity, &mut MenuHandler)>,
+    mut runner: MenuActionRunner,
+) {
+    if !menu_action_state.just_pressed(&MenuAction::Accept) {
+        return;
+    }
+    let Ok((entity, mut menu)) = menu_query.get_single_mut() else {
+        return;
+    };
+    let Some(action) = menu.selected_entry().map(|entry| entry.action.clone()) else {
+        return;
+    };
+    runner.run(entity, &mut menu, action);
+}
+
+/// Escape closes the open submenu or runs the menu escape action. Once handled, Escape is cleared
+/// so no other system handles it again.
+pub fn menu_escape_system(
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut menu_query: Query<(Entity, &mut MenuHandler)>,
+    mut runner: MenuActionRunner,
+) {
+    if !keys.just_pressed(KeyCode::Escape) {
+        return;
+    }
+    let Ok((entity, mut menu)) = menu_query.get_single_mut() else {
+        return;
+    };
+    let action = if menu.parents.is_empty() {
+        let Some(action) = menu.escape_action.clone() else {
+            return;
+        };
+        action
+    } else {
+        MenuEntryAction::Back
+    };
+    keys.clear_just_pressed(KeyCode::Escape);
+    runner.run(entity, &mut menu, action);
+}
+
+/// The pause key toggles the pause, Escape pauses the flight while the pause menu handles it back.
+pub fn pause_input_system(
+    game_state: Res<State<GameState>>,
+    keys: Res<ButtonInput<KeyCode>>,
     menu_action_state: Res<ActionState<MenuAction>>,
-    mut app_exit_events: EventWriter<AppExit>,
-    menu: Query<&MenuHandler>,
+    mut next_game_state: ResMut<NextState<GameState>>,
 ) {
-    if menu_action_state.just_pressed(&MenuAction::PauseUnpause) {
+    if keys.just_pressed(KeyCode::Escape) && game_state.get() == &GameState::Landing {
+        next_game_state.set(GameState::Paused);
+    } else if menu_action_state.just_pressed(&MenuAction::PauseUnpause) {
         if game_state.get() == &GameState::Landing {
             next_game_state.set(GameState::Paused);
         }
@@ -59,31 +133,4 @@ pub fn game_menu_input_system(
             next_game_state.set(GameState::Landing);
         }
     }
-    if let Ok(menu) = menu.get_single() {
-        if menu_action_state.just_pressed(&MenuAction::Accept) {
-            if game_state.get() == &GameState::Paused {
-                match menu.selected_id {
-                    0 => {
-                        next_game_state.set(GameState::Landing);
-                    }
-                    1 => {
-                        next_app_state.set(AppState::Menu);
-                    }
-                    _ => {
-                        app_exit_events.send(AppExit::Success);
-                    }
-                }
-            }
-            if game_state.get() == &GameState::GameOver {
-                match menu.selected_id {
-                    0 => {
-                        next_app_state.set(AppState::Menu);
-                    }
-                    _ => {
-                        app_exit_events.send(AppExit::Success);
-                    }
-                }
-            }
-        }
-    }
 }
@@ -1,16 +1,27 @@
 mod handler;
 mod interaction;
+mod text_input;
 
 use bevy::prelude::*;
+use bevy::ui::UiSystem;
+use bevy_persistent::Persistent;
 use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::asset_loader::UiAssets;
-use crate::game::{Resettable, Scores, FUEL_QUANTITY};
+use crate::controls::{not_rebinding, KeyBindings};
+use crate::game::Resettable;
+use crate::leaderboard::run_qualifies_for_leaderboard;
+use crate::localization::Localization;
+use crate::profile::{play_action, Profiles};
+use crate::save::{SavedRun, CONTINUE_RUN_EVENT};
+use crate::settings::OPEN_SETTINGS_EVENT;
 use crate::state::{AppState, GameState};
 use crate::MAIN_TITLE;
 
 pub use handler::*;
 pub use interaction::*;
+pub use text_input::*;
 
 pub const PRIMARY_COLOR: Color = Color::srgb(0.54, 0.13, 0.07);
 pub const SECONDARY_COLOR: Color = Color::srgb(0.45, 0.68, 0.74);
@@ -18,115 +29,279 @@ pub const GREY_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
 pub const DARK_GREY_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
 pub const BLACK_COLOR: Color = Color::srgb(0.04, 0.04, 0.04);
 
-#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
+#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect, Serialize, Deserialize)]
 pub enum MenuAction {
     MenuUp,
     MenuDown,
     Accept,
     PauseUnpause,
+    ToggleAutopilot,
+    AutopilotTarget,
+    ToggleTrajectory,
+    ToggleFullscreen,
 }
 
 pub struct MenuPlugin;
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Init), setup)
+        app.add_event::<MenuCustomEvent>()
+            .add_event::<TextInputEvent>()
+            .init_resource::<ScreenBackStack>()
+            .add_systems(OnEnter(AppState::Init), setup)
             .add_systems(OnEnter(AppState::Menu), spawn_main_menu)
             .add_systems(OnEnter(AppState::Instructions), spawn_instructions_menu)
             .add_systems(OnEnter(AppState::Credits), spawn_credits_menu)
             .add_systems(OnEnter(GameState::Paused), spawn_pause_menu)
-            .add_systems(OnEnter(GameState::GameOver), spawn_game_over_menu)
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                (
+                    despawn_resettable_entities,
+                    spawn_game_over_menu.run_if(not(run_qualifies_for_leaderboard)),
+                ),
+            )
             .add_systems(
                 Update,
                 (
-                    main_menu_input_system,
-                    menu_selection_system,
+                    menu_accept_system.run_if(not_rebinding),
+                    menu_escape_system.run_if(not_rebinding),
+                    menu_selection_system.run_if(not_rebinding),
                     menu_blink_system,
+                    text_input_system,
                 ),
             )
+            .add_systems(Update, pause_input_system.run_if(in_state(AppState::Game)))
             .add_systems(
-                Update,
-                game_menu_input_system.run_if(in_state(AppState::Game)),
+                PreUpdate,
+                menu_pointer_system
+                    .after(UiSystem::Focus)
+                    .in_set(InputManagerSystem::ManualControl)
+                    .run_if(not_rebinding),
             );
     }
 }
 
 fn setup(mut commands: Commands) {
-    let mut input_map = InputMap::<MenuAction>::new([
-        (MenuAction::Accept, KeyCode::Enter),
-        (MenuAction::PauseUnpause, KeyCode::KeyP),
-        (MenuAction::MenuUp, KeyCode::KeyW),
-        (MenuAction::MenuUp, KeyCode::ArrowUp),
-        (MenuAction::MenuDown, KeyCode::KeyS),
-        (MenuAction::MenuDown, KeyCode::ArrowDown),
-    ]);
-    input_map.insert(MenuAction::PauseUnpause, GamepadButtonType::Start);
-    input_map.insert(MenuAction::Accept, GamepadButtonType::South);
-    // insert MenuAction resources
-    commands.insert_resource(input_map);
+    // insert MenuAction resources, the InputMap is inserted from the persisted key bindings
     commands.insert_resource(ActionState::<MenuAction>::default());
 }
 
-fn spawn_main_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
+fn spawn_main_menu(
+    mut commands: Commands,
+    assets: ResMut<UiAssets>,
+    localization: Res<Localization>,
+    saved_run: Res<Persistent<SavedRun>>,
+    profiles: Res<Persistent<Profiles>>,
+) {
     let entity = MenuHandler {
         main_text: MAIN_TITLE.to_string(),
         main_text_color: SECONDARY_COLOR,
         main_text_blink: false,
         selected_id: 0,
         entries: vec![
-            "Play".into(),
-            "Instructions".into(),
-            "Credits".into(),
-            "Exit".into(),
+            MenuEntry::new(
+                localization.text("menu.continue"),
+                MenuEntryAction::Custom(CONTINUE_RUN_EVENT),
+            )
+            .enabled(saved_run.run.is_some()),
+            MenuEntry::new(
+                localization.text("menu.play"),
+                play_action(&profiles, &localization),
+            ),
+            MenuEntry::new(
+                localization.text("menu.instructions"),
+                MenuEntryAction::OpenScreen(AppState::Instructions),
+            ),
+            MenuEntry::new(
+                localization.text("menu.controls"),
+                MenuEntryAction::OpenScreen(AppState::Controls),
+            ),
+            MenuEntry::new(
+                localization.text("menu.settings"),
+                MenuEntryAction::OpenScreen(AppState::Settings),
+            ),
+            MenuEntry::new(
+                localization.text("menu.records"),
+                MenuEntryAction::Submenu(Box::new(records_menu(&localization))),
+            ),
+            MenuEntry::new(
+                localization.text("menu.credits"),
+                MenuEntryAction::OpenScreen(AppState::Credits),
+            ),
+            MenuEntry::new(
+                localization.text("menu.profiles"),
+                MenuEntryAction::OpenScreen(AppState::Profiles),
+            ),
+            MenuEntry::new(
+                localization.text("menu.exit"),
+                confirmation("menu.confirm_exit", MenuEntryAction::Quit, &localization),
+            ),
         ],
+        escape_action: Some(confirmation(
+            "menu.confirm_exit",
+            MenuEntryAction::Quit,
+            &localization,
+        )),
+        ..default()
     }
     .spawn(&mut commands, assets.font_kenvector.clone());
     commands.entity(entity).insert(StateScoped(AppState::Menu));
 }
 
-fn spawn_game_over_menu(
+fn despawn_resettable_entities(
     resettable_text_query: Query<Entity, With<Resettable>>,
-    assets: ResMut<UiAssets>,
     mut commands: Commands,
-    mut score: ResMut<Scores>,
 ) {
     for entity in resettable_text_query.iter() {
         commands.entity(entity).despawn_recursive();
     }
+}
+
+fn spawn_game_over_menu(
+    assets: ResMut<UiAssets>,
+    localization: Res<Localization>,
+    mut commands: Commands,
+) {
+    spawn_game_over_menu_handler(&mut commands, &assets, &localization);
+}
+
+/// Also used once the player has typed a name for the leaderboard.
+pub fn spawn_game_over_menu_handler(
+    commands: &mut Commands,
+    assets: &UiAssets,
+    localization: &Localization,
+) {
     let entity = MenuHandler {
-        main_text: "Game Over".into(),
+        main_text: localization.text("menu.game_over").into(),
         main_text_color: Color::srgb_u8(0xAA, 0x22, 0x22),
         main_text_blink: false,
         selected_id: 0,
-        entries: vec!["Menu".into(), "Exit".into()],
+        entries: vec![
+            MenuEntry::new(
+                localization.text("menu.menu"),
+                MenuEntryAction::OpenScreen(AppState::Menu),
+            ),
+            MenuEntry::new(localization.text("menu.exit"), MenuEntryAction::Quit),
+        ],
+        ..default()
     }
-    .spawn(&mut commands, assets.font_kenvector.clone());
+    .spawn(commands, assets.font_kenvector.clone());
     commands
         .entity(entity)
         .insert(StateScoped(GameState::GameOver));
-    score.fuel_quantity = FUEL_QUANTITY;
 }
 
-fn spawn_pause_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
+fn spawn_pause_menu(
+    mut commands: Commands,
+    assets: ResMut<UiAssets>,
+    localization: Res<Localization>,
+) {
+    spawn_pause_menu_handler(&mut commands, &assets, &localization);
+}
+
+/// Also used to come back from the settings menu opened while paused.
+pub fn spawn_pause_menu_handler(
+    commands: &mut Commands,
+    assets: &UiAssets,
+    localization: &Localization,
+) {
     let entity = MenuHandler {
-        main_text: "Pause".into(),
+        main_text: localization.text("menu.pause").into(),
         main_text_color: Color::srgb_u8(0xF8, 0xE4, 0x73),
         main_text_blink: true,
         selected_id: 0,
-        entries: vec!["Resume".into(), "Menu".into(), "Exit".into()],
+        entries: vec![
+            MenuEntry::new(localization.text("menu.resume"), MenuEntryAction::Resume),
+            MenuEntry::new(
+                localization.text("menu.settings"),
+                MenuEntryAction::Custom(OPEN_SETTINGS_EVENT),
+            ),
+            MenuEntry::new(
+                localization.text("menu.menu"),
+                confirmation(
+                    "menu.confirm_menu",
+                    MenuEntryAction::OpenScreen(AppState::Menu),
+                    localization,
+                ),
+            ),
+            MenuEntry::new(
+                localization.text("menu.exit"),
+                confirmation("menu.confirm_exit", MenuEntryAction::Quit, localization),
+            ),
+        ],
+        escape_action: Some(MenuEntryAction::Resume),
+        ..default()
     }
-    .spawn(&mut commands, assets.font_kenvector.clone());
+    .spawn(commands, assets.font_kenvector.clone());
     commands
         .entity(entity)
         .insert(StateScoped(GameState::Paused));
 }
 
-fn spawn_instructions_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
+/// Submenu asking to confirm `action`, "no" is selected first and Escape also cancels.
+pub fn confirmation(
+    question_key: &str,
+    action: MenuEntryAction,
+    localization: &Localization,
+) -> MenuEntryAction {
+    MenuEntryAction::Submenu(Box::new(MenuHandler {
+        main_text: localization.text(question_key).into(),
+        main_text_color: SECONDARY_COLOR,
+        main_text_blink: false,
+        selected_id: 0,
+        entries: vec![
+            MenuEntry::new(localization.text("common.no"), MenuEntryAction::Back),
+            MenuEntry::new(localization.text("common.yes"), action),
+        ],
+        escape_action: Some(MenuEntryAction::Back),
+        ..default()
+    }))
+}
+
+/// Hall of Fame and statistics, grouped to keep the main menu short.
+fn records_menu(localization: &Localization) -> MenuHandler {
+    MenuHandler {
+        main_text: localization.text("menu.records").into(),
+        main_text_color: SECONDARY_COLOR,
+        main_text_blink: false,
+        selected_id: 0,
+        entries: vec![
+            MenuEntry::new(
+                localization.text("menu.hall_of_fame"),
+                MenuEntryAction::OpenScreen(AppState::HallOfFame),
+            ),
+            MenuEntry::new(
+                localization.text("menu.statistics"),
+                MenuEntryAction::OpenScreen(AppState::Statistics),
+            ),
+            MenuEntry::new(localization.text("menu.menu"), MenuEntryAction::Back),
+        ],
+        escape_action: Some(MenuEntryAction::Back),
+        ..default()
+    }
+}
+
+/// Entries of the instructions, hall of fame, statistics and credits screens.
+pub fn screen_entries(localization: &Localization) -> Vec<MenuEntry> {
+    vec![
+        MenuEntry::new(localization.text("menu.menu"), MenuEntryAction::Back),
+        MenuEntry::new(localization.text("menu.exit"), MenuEntryAction::Quit),
+    ]
+}
+
+fn spawn_instructions_menu(
+    mut commands: Commands,
+    assets: ResMut<UiAssets>,
+    key_bindings: Res<Persistent<KeyBindings>>,
+    localization: Res<Localization>,
+) {
     let entity = MenuHandler {
         main_text: "".into(),
         main_text_color: GREY_COLOR,
         main_text_blink: false,
         selected_id: 0,
-        entries: vec!["Menu".into(), "Exit".into()],
+        entries: screen_entries(&localization),
+        escape_action: Some(MenuEntryAction::Back),
+        ..default()
     }
     .spawn(&mut commands, assets.font_kenvector.clone());
     commands
@@ -154,7 +329,7 @@ fn spawn_instructions_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
                     ..default()
                 },
                 text: Text::from_section(
-                    "Land on one of three platforms. Vertical velocity must come within the yellow area of the scale. The score, according to the velocity, is multiplied by the number under platform. You'll be refueled on a successful landing. Every landing the gravity increases.",
+                    localization.text("instructions.goal"),
                     TextStyle {
                         font: assets.font_kenvector.clone(),
                         font_size: 25.0,
@@ -169,7 +344,7 @@ fn spawn_instructions_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
                     ..default()
                 },
                 text: Text::from_section(
-                    "Press A or arrow LEFT key to push spaceship right.\nPress D or arrow RIGHT key to push spaceship left.\nPress 2 or SPACE key to enable a big thrust up.\nPress W or arrow UP key to enable a medium thrust up.\nPress S or arrow DOWN key to enable a small thrust up.",
+                    key_bindings.instructions_text(&localization),
                     TextStyle {
                         font: assets.font_kenvector.clone(),
                         font_size: 25.0,
@@ -181,13 +356,19 @@ fn spawn_instructions_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
         });
 }
 
-fn spawn_credits_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
+fn spawn_credits_menu(
+    mut commands: Commands,
+    assets: ResMut<UiAssets>,
+    localization: Res<Localization>,
+) {
     let entity = MenuHandler {
         main_text: "".into(),
         main_text_color: PRIMARY_COLOR,
         main_text_blink: false,
         selected_id: 0,
-        entries: vec!["Menu".into(), "Exit".into()],
+        entries: screen_entries(&localization),
+        escape_action: Some(MenuEntryAction::Back),
+        ..default()
     }
     .spawn(&mut commands, assets.font_kenvector.clone());
     commands
@@ -215,7 +396,7 @@ fn spawn_credits_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
                     ..default()
                 },
                 text: Text::from_section(
-                    "Code",
+                    localization.text("credits.code"),
                     TextStyle {
                         font: assets.font_kenvector.clone(),
                         font_size: 50.0,
@@ -255,7 +436,7 @@ fn spawn_credits_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
                     ..default()
                 },
                 text: Text::from_section(
-                    "Pixel Art",
+                    localization.text("credits.pixel_art"),
                     TextStyle {
                         font: assets.font_kenvector.clone(),
                         font_size: 50.0,
@@ -295,7 +476,7 @@ fn spawn_credits_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
                     ..default()
                 },
                 text: Text::from_section(
-                    "Music & Sound Effects",
+                    localization.text("credits.music"),
                     TextStyle {
                         font: assets.font_kenvector.clone(),
                         font_size: 50.0,
@@ -335,7 +516,7 @@ fn spawn_credits_menu(mut commands: Commands, assets: ResMut<UiAssets>) {
                     ..default()
                 },
                 text: Text::from_section(
-                    "Acknowledgements",
+                    localization.text("credits.acknowledgements"),
                     TextStyle {
                         font: assets.font_kenvector.clone(),
                         font_size: 50.0,
@@ -0,0 +1,94 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+
+/// Text typed by the player, shown by the `Text` of the same entity followed by a cursor.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct TextInput {
+    pub value: String,
+    pub max_length: usize,
+}
+
+impl TextInput {
+    pub fn new(value: impl Into<String>, max_length: usize) -> Self {
+        Self {
+            value: value.into(),
+            max_length,
+        }
+    }
+
+    /// Letters, digits and a few separators, up to `max_length` characters.
+    pub fn push(&mut self, character: char) {
+        let accepted = character.is_alphanumeric() || matches!(character, ' ' | '-' | '_' | '.');
+        if accepted && self.value.chars().count() < self.max_length {
+            self.value.push(character);
+        }
+    }
+
+    pub fn label(&self) -> String {
+        format!("{}_", self.value)
+    }
+}
+
+/// Hotkeys are typed letters while a text input is shown.
+pub fn no_text_input(inputs_query: Query<(), With<TextInput>>) -> bool {
+    inputs_query.is_empty()
+}
+
+/// Sent when Enter submits a text input, or Escape cancels it.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct TextInputEvent {
+    pub entity: Entity,
+    pub value: String,
+    pub submitted: bool,
+}
+
+pub fn text_input_system(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut inputs_query: Query<(Entity, &mut TextInput, &mut Text)>,
+    mut text_input_events: EventWriter<TextInputEvent>,
+) {
+    let keys: Vec<Key> = keyboard_events
+        .read()
+        .filter(|event| event.state == ButtonState::Pressed)
+        .map(|event| event.logical_key.clone())
+        .collect();
+    for (entity, mut input, mut text) in inputs_query.iter_mut() {
+        for key in keys.iter() {
+            match key {
+                Key::Character(characters) => {
+                    for character in characters.chars() {
+                        input.push(character);
+                    }
+                }
+                Key::Space => input.push(' '),
+                Key::Backspace => {
+                    input.value.pop();
+                }
+                Key::Enter | Key::Escape => {
+                    text_input_events.send(TextInputEvent {
+                        entity,
+                        value: input.value.trim().to_string(),
+                        submitted: *key == Key::Enter,
+                    });
+                    break;
+                }
+                _ => {}
+            }
+        }
+        if input.is_changed() {
+            text.sections[0].value = input.label();
+        }
+    }
+}
+
+#[test]
+fn text_input_test() {
+    let mut input = TextInput::new("Ada", 5);
+    input.push('!');
+    input.push(' ');
+    input.push('L');
+    input.push('X');
+    assert_eq!(input.value, "Ada L");
+    assert_eq!(input.label(), "Ada L_");
+}
@@ -1,59 +1,98 @@
 use crate::asset_loader::MusicAssets;
 use crate::audio::MusicBeginSoundEffect;
-use crate::game::{InGameSet, OutOfFuelEvent, Resettable, Scores};
+use crate::collider::player_landed_collisions_system;
+use crate::explosion::SpawnExplosionEvent;
+use crate::game::{InGameSet, OutOfFuelEvent, Resettable, Scores, SpaceshipJustLandedEvent};
+use crate::input_buffer::ThrusterTickInput;
+use crate::landing_rules::LandingRules;
+use crate::radar::{update_radar_altimeter_system, RadarAltimeter};
+use crate::settings::Settings;
 use crate::spaceship::PlayerAction;
 use crate::state::{GameState, TenSecondsTimer};
 use avian2d::{math::*, prelude::*};
 use bevy::audio::PlaybackMode;
 use bevy::prelude::*;
-use leafwing_input_manager::prelude::*;
+use bevy_persistent::Persistent;
 
-const BIG_THRUST: f32 = 0.75;
-const MEDIUM_THRUST: f32 = 0.55;
-const SMALL_THRUST: f32 = 0.45;
+pub const BIG_THRUST: f32 = 0.75;
+pub const MEDIUM_THRUST: f32 = 0.55;
+pub const SMALL_THRUST: f32 = 0.45;
 
 pub struct CharacterControllerPlugin;
 
 impl Plugin for CharacterControllerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            OnEnter(GameState::Landing),
-            |mut physics_time: ResMut<Time<Physics>>,
-             mut commands: Commands,
-             music_assets: Res<MusicAssets>| {
-                physics_time.unpause();
-                commands.spawn((
-                    Resettable,
-                    MusicBeginSoundEffect,
-                    AudioBundle {
-                        source: music_assets.music_begin.clone(),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Once,
-                            ..default()
+        app.add_plugins(LanderPhysicsPlugin)
+            .add_systems(
+                OnEnter(GameState::Landing),
+                |mut physics_time: ResMut<Time<Physics>>,
+                 mut commands: Commands,
+                 music_assets: Res<MusicAssets>,
+                 settings: Res<Persistent<Settings>>| {
+                    physics_time.unpause();
+                    commands.spawn((
+                        Resettable,
+                        MusicBeginSoundEffect,
+                        AudioBundle {
+                            source: music_assets.music_begin.clone(),
+                            settings: PlaybackSettings {
+                                mode: PlaybackMode::Once,
+                                volume: settings.music_volume(),
+                                ..default()
+                            },
                         },
-                    },
-                ));
-                commands
-                    .insert_resource(TenSecondsTimer(Timer::from_seconds(10.0, TimerMode::Once)));
-            },
-        )
-        .add_systems(
-            OnEnter(GameState::Paused),
-            |mut physics_time: ResMut<Time<Physics>>| {
-                physics_time.pause();
-            },
-        )
-        .add_systems(
-            FixedUpdate,
-            (
-                update_ready_to_land_system,
-                movement_system,
-                apply_movement_damping_system,
+                    ));
+                    commands.insert_resource(TenSecondsTimer(Timer::from_seconds(
+                        10.0,
+                        TimerMode::Once,
+                    )));
+                },
             )
-                .chain()
-                .run_if(in_state(GameState::Landing))
-                .in_set(InGameSet::Physics),
-        );
+            .add_systems(
+                OnEnter(GameState::Paused),
+                |mut physics_time: ResMut<Time<Physics>>| {
+                    physics_time.pause();
+                },
+            );
+    }
+}
+
+/// Fixed tick lander simulation (thrusters, damping & landing collisions) without any rendering,
+/// audio or input handling, shared by the game and the headless environment.
+pub struct LanderPhysicsPlugin;
+
+impl Plugin for LanderPhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ThrusterTickInput>()
+            .init_resource::<LandingRules>()
+            .add_event::<SpaceshipJustLandedEvent>()
+            .add_event::<OutOfFuelEvent>()
+            .add_event::<SpawnExplosionEvent>()
+            .configure_sets(
+                FixedUpdate,
+                (
+                    InGameSet::Input,
+                    InGameSet::Collisions,
+                    InGameSet::Physics,
+                    InGameSet::SpeedBar,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    player_landed_collisions_system.in_set(InGameSet::Collisions),
+                    (
+                        update_ready_to_land_system,
+                        movement_system,
+                        apply_movement_damping_system,
+                    )
+                        .chain()
+                        .in_set(InGameSet::Physics),
+                    update_radar_altimeter_system.in_set(InGameSet::SpeedBar),
+                )
+                    .run_if(in_state(GameState::Landing)),
+            );
     }
 }
 
@@ -71,7 +110,7 @@ pub struct MovementAcceleration(Scalar);
 pub struct MovementDampingFactor(Scalar);
 
 #[derive(Component)]
-pub struct JumpImpulse(Scalar);
+pub struct JumpImpulse(pub Scalar);
 
 #[derive(Bundle)]
 pub struct CharacterControllerBundle {
@@ -131,14 +170,16 @@ impl CharacterControllerBundle {
     }
 }
 
+/// Checked against the rules of the platform seen by the radar, the default ones elsewhere.
 fn update_ready_to_land_system(
     mut commands: Commands,
-    mut query: Query<(Entity, &LinearVelocity), With<CharacterController>>,
+    landing_rules: Res<LandingRules>,
+    mut query: Query<(Entity, &LinearVelocity, &RadarAltimeter), With<CharacterController>>,
 ) {
-    let Ok((entity, linear_velocity)) = query.get_single_mut() else {
+    let Ok((entity, linear_velocity, radar_altimeter)) = query.get_single_mut() else {
         return;
     };
-    if linear_velocity.y < 1.0 && linear_velocity.y > -35.0 {
+    if landing_rules.is_safe(linear_velocity.y, radar_altimeter.platform_below()) {
         commands.entity(entity).insert(ReadyToLand);
     } else {
         commands.entity(entity).remove::<ReadyToLand>();
@@ -147,36 +188,31 @@ fn update_ready_to_land_system(
 
 fn movement_system(
     time: Res<Time>,
+    tick_input: Res<ThrusterTickInput>,
     mut out_of_fuel_events: EventWriter<OutOfFuelEvent>,
     mut scores: ResMut<Scores>,
-    mut controllers: Query<(
-        &ActionState<PlayerAction>,
-        &MovementAcceleration,
-        &JumpImpulse,
-        &mut LinearVelocity,
-    )>,
+    mut controllers: Query<(&MovementAcceleration, &JumpImpulse, &mut LinearVelocity)>,
 ) {
     let delta_time = time.delta_seconds_f64().adjust_precision();
-    for (action_state, movement_acceleration, jump_impulse, mut linear_velocity) in &mut controllers
-    {
+    for (movement_acceleration, jump_impulse, mut linear_velocity) in &mut controllers {
         if scores.fuel_quantity > 0.0 {
-            if action_state.pressed(&PlayerAction::LeftThruster) {
+            if tick_input.pressed(PlayerAction::LeftThruster) {
                 linear_velocity.x += movement_acceleration.0 * delta_time;
                 scores.fuel_quantity -= 20.0 * time.delta_seconds();
             }
-            if action_state.pressed(&PlayerAction::RightThruster) {
+            if tick_input.pressed(PlayerAction::RightThruster) {
                 linear_velocity.x += -movement_acceleration.0 * delta_time;
                 scores.fuel_quantity -= 20.0 * time.delta_seconds();
             }
-            if action_state.pressed(&PlayerAction::MainThrusterBig) {
+            if tick_input.pressed(PlayerAction::MainThrusterBig) {
                 linear_velocity.y += jump_impulse.0 * BIG_THRUST;
                 scores.fuel_quantity -= 100.0 * time.delta_seconds();
             }
-            if action_state.pressed(&PlayerAction::MainThrusterMedium) {
+            if tick_input.pressed(PlayerAction::MainThrusterMedium) {
                 linear_velocity.y += jump_impulse.0 * MEDIUM_THRUST;
                 scores.fuel_quantity -= 50.0 * time.delta_seconds();
             }
-            if action_state.pressed(&PlayerAction::MainThrusterSmall) {
+            if tick_input.pressed(PlayerAction::MainThrusterSmall) {
                 linear_velocity.y += jump_impulse.0 * SMALL_THRUST;
                 scores.fuel_quantity -= 20.0 * time.delta_seconds();
             }
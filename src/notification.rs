@@ -0,0 +1,315 @@
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+use bevy::text::Text2dBounds;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::asset_loader::UiAssets;
+use crate::menu::BLACK_COLOR;
+use crate::state::{AppState, GameState};
+use crate::WINDOW_HEIGHT;
+
+const INFO_PANEL_WIDTH: f32 = 400.0;
+const INFO_PANEL_HEIGHT: f32 = 110.0;
+const INFO_PANEL_LINE_HEIGHT: f32 = 40.0;
+const INFO_PANEL_POSITION: Vec2 = Vec2::new(0.0, WINDOW_HEIGHT / 4.0);
+/// Keys acknowledging a panel, the same ones continuing after a landing.
+const DISMISS_KEYS: [KeyCode; 3] = [KeyCode::Enter, KeyCode::ControlLeft, KeyCode::ControlRight];
+const MAX_VISIBLE_TOASTS: usize = 3;
+const TOAST_DURATION: Duration = Duration::from_millis(2500);
+const TOAST_FADE_SECONDS: f32 = 0.4;
+const TOAST_TOP: f32 = 60.0;
+const TOAST_SPACING: f32 = 40.0;
+
+pub struct NotificationPlugin;
+
+impl Plugin for NotificationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ShowPanelEvent>()
+            .add_event::<ShowToastEvent>()
+            .init_resource::<ToastQueue>()
+            .add_systems(OnEnter(GameState::Setup), clear_panels_system)
+            .add_systems(OnEnter(GameState::GameOver), clear_panels_system)
+            .add_systems(
+                Update,
+                (
+                    spawn_panels_system,
+                    dismiss_panels_system,
+                    queue_toasts_system,
+                    spawn_queued_toasts_system,
+                    fade_toasts_system,
+                )
+                    .chain(),
+            );
+    }
+}
+
+// Systems
+fn spawn_panels_system(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    mut events_reader: EventReader<ShowPanelEvent>,
+) {
+    for event in events_reader.read() {
+        let size = Vec2::new(INFO_PANEL_WIDTH, event.height());
+        // the title stays above the lines, all of them centered in the panel
+        let top = (event.lines.len() as f32) * INFO_PANEL_LINE_HEIGHT / 2.0;
+        commands
+            .spawn((
+                StateScoped(AppState::Game),
+                InfoPanel {
+                    dismiss: event.dismiss.timer(),
+                },
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: BLACK_COLOR,
+                        custom_size: Some(size),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(INFO_PANEL_POSITION.extend(11.0)),
+                    ..default()
+                },
+                RenderLayers::layer(2),
+            ))
+            .with_children(|builder| {
+                let mut spawn_text = |text: &str, font_size: f32, y: f32| {
+                    builder.spawn((
+                        Text2dBundle {
+                            text: Text::from_section(
+                                text,
+                                TextStyle {
+                                    font: assets.font_vt323.clone(),
+                                    font_size,
+                                    color: Color::WHITE,
+                                },
+                            )
+                            .with_justify(JustifyText::Left),
+                            text_2d_bounds: Text2dBounds { size },
+                            transform: Transform::from_translation(Vec3::new(0.0, y, 1.0)),
+                            ..default()
+                        },
+                        RenderLayers::layer(2),
+                    ));
+                };
+                spawn_text(&event.title, 60.0, top);
+                for (index, line) in event.lines.iter().enumerate() {
+                    spawn_text(
+                        line,
+                        30.0,
+                        top - (index + 1) as f32 * INFO_PANEL_LINE_HEIGHT,
+                    );
+                }
+            });
+    }
+}
+
+fn dismiss_panels_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut panels_query: Query<(Entity, &mut InfoPanel)>,
+) {
+    let key_pressed = keys.any_just_pressed(DISMISS_KEYS);
+    for (entity, mut panel) in panels_query.iter_mut() {
+        let dismissed = match panel.dismiss.as_mut() {
+            Some(timer) => timer.tick(time.delta()).finished(),
+            None => key_pressed,
+        };
+        if dismissed {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn clear_panels_system(mut commands: Commands, panels_query: Query<Entity, With<InfoPanel>>) {
+    for entity in panels_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn queue_toasts_system(
+    mut events_reader: EventReader<ShowToastEvent>,
+    mut toast_queue: ResMut<ToastQueue>,
+    toasts_query: Query<&Toast>,
+) {
+    for ShowToastEvent(text) in events_reader.read() {
+        let already_shown = toasts_query.iter().any(|toast| &toast.text == text);
+        if !already_shown {
+            toast_queue.push(text.clone());
+        }
+    }
+}
+
+fn spawn_queued_toasts_system(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    mut toast_queue: ResMut<ToastQueue>,
+    toasts_query: Query<&Toast>,
+) {
+    let visible = toasts_query.iter().count();
+    for _ in visible..MAX_VISIBLE_TOASTS {
+        let Some(text) = toast_queue.pending.pop_front() else {
+            return;
+        };
+        commands
+            .spawn((
+                Toast {
+                    text: text.clone(),
+                    timer: Timer::new(TOAST_DURATION, TimerMode::Once),
+                },
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(TOAST_TOP),
+                        width: Val::Percent(100.0),
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    z_index: ZIndex::Global(10),
+                    ..default()
+                },
+            ))
+            .with_children(|builder| {
+                builder
+                    .spawn(NodeBundle {
+                        style: Style {
+                            padding: UiRect::axes(Val::Px(16.0), Val::Px(4.0)),
+                            ..default()
+                        },
+                        background_color: BLACK_COLOR.with_alpha(0.0).into(),
+                        ..default()
+                    })
+                    .with_children(|builder| {
+                        builder.spawn(TextBundle::from_section(
+                            text,
+                            TextStyle {
+                                font: assets.font_vt323.clone(),
+                                font_size: 30.0,
+                                color: Color::WHITE.with_alpha(0.0),
+                            },
+                        ));
+                    });
+            });
+    }
+}
+
+/// Fades every toast in and out and stacks them downwards, the oldest on top.
+fn fade_toasts_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut toasts_query: Query<(Entity, &mut Toast, &mut Style, &Children)>,
+    mut backgrounds_query: Query<(&mut BackgroundColor, &Children), Without<Toast>>,
+    mut texts_query: Query<&mut Text>,
+) {
+    let mut toasts: Vec<_> = toasts_query.iter_mut().collect();
+    toasts.sort_by_key(|(_, toast, _, _)| toast.timer.remaining());
+    let mut slot = 0;
+    for (entity, mut toast, mut style, children) in toasts {
+        if toast.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        style.top = Val::Px(TOAST_TOP + slot as f32 * TOAST_SPACING);
+        slot += 1;
+        let alpha = toast_alpha(toast.timer.elapsed_secs(), TOAST_DURATION.as_secs_f32());
+        for &child in children.iter() {
+            let Ok((mut background_color, texts)) = backgrounds_query.get_mut(child) else {
+                continue;
+            };
+            background_color.0.set_alpha(alpha * 0.8);
+            for &text in texts.iter() {
+                if let Ok(mut text) = texts_query.get_mut(text) {
+                    text.sections[0].style.color.set_alpha(alpha);
+                }
+            }
+        }
+    }
+}
+
+/// Opacity of a toast `elapsed` seconds after it appeared.
+fn toast_alpha(elapsed: f32, duration: f32) -> f32 {
+    let fade_in = elapsed / TOAST_FADE_SECONDS;
+    let fade_out = (duration - elapsed) / TOAST_FADE_SECONDS;
+    fade_in.min(fade_out).clamp(0.0, 1.0)
+}
+
+// Events
+/// Shows a panel in the middle of the play area until dismissed.
+#[derive(Event, Debug, Clone)]
+pub struct ShowPanelEvent {
+    pub title: String,
+    pub lines: Vec<String>,
+    pub dismiss: PanelDismiss,
+}
+
+impl ShowPanelEvent {
+    fn height(&self) -> f32 {
+        INFO_PANEL_HEIGHT + (self.lines.len().max(1) - 1) as f32 * INFO_PANEL_LINE_HEIGHT
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanelDismiss {
+    /// Enter or Control, panels are also cleared when a new landing is set up
+    OnKey,
+    After(Duration),
+}
+
+impl PanelDismiss {
+    fn timer(&self) -> Option<Timer> {
+        match self {
+            PanelDismiss::OnKey => None,
+            PanelDismiss::After(duration) => Some(Timer::new(*duration, TimerMode::Once)),
+        }
+    }
+}
+
+/// Shows a short non-blocking message at the top of the screen.
+#[derive(Event, Debug, Clone)]
+pub struct ShowToastEvent(pub String);
+
+// Resources
+/// Toasts waiting for a free slot, the same text is never queued twice.
+#[derive(Resource, Debug, Default)]
+struct ToastQueue {
+    pending: VecDeque<String>,
+}
+
+impl ToastQueue {
+    fn push(&mut self, text: String) {
+        if !self.pending.contains(&text) {
+            self.pending.push_back(text);
+        }
+    }
+}
+
+// Components
+#[derive(Component, Debug)]
+struct InfoPanel {
+    /// None when dismissed by a key
+    dismiss: Option<Timer>,
+}
+
+#[derive(Component, Debug)]
+struct Toast {
+    text: String,
+    timer: Timer,
+}
+
+#[test]
+fn notification_test() {
+    let mut toast_queue = ToastQueue::default();
+    toast_queue.push("Low fuel".into());
+    toast_queue.push("Low fuel".into());
+    toast_queue.push("New high score!".into());
+    assert_eq!(toast_queue.pending.len(), 2);
+    assert_eq!(toast_alpha(0.0, 2.5), 0.0);
+    assert_eq!(toast_alpha(1.0, 2.5), 1.0);
+    assert_eq!(toast_alpha(2.5, 2.5), 0.0);
+    let panel = ShowPanelEvent {
+        title: "Out of fuel".into(),
+        lines: vec![],
+        dismiss: PanelDismiss::OnKey,
+    };
+    assert_eq!(panel.height(), INFO_PANEL_HEIGHT);
+}
@@ -1,9 +1,9 @@
 use avian2d::math::PI;
 use bevy::prelude::*;
 use bevy_hanabi::prelude::*;
-use leafwing_input_manager::prelude::*;
 
 use crate::game::Scores;
+use crate::input_buffer::ThrusterTickInput;
 use crate::spaceship::{
     LeftHorizontalThrusterEffect, Player, PlayerAction, RightHorizontalThrusterEffect,
     VerticalThrusterEffect,
@@ -205,73 +205,76 @@ fn add_right_thrust_particles_to_spaceship_system(
 }
 
 fn update_vertical_thrust_particles_system(
-    scores: ResMut<Scores>,
-    player: Query<(&ActionState<PlayerAction>, &Children), Changed<ActionState<PlayerAction>>>,
+    scores: Res<Scores>,
+    tick_input: Res<ThrusterTickInput>,
+    player: Query<&Children, With<Player>>,
     mut vertical_thruster_effect_query: Query<&mut EffectSpawner, With<VerticalThrusterEffect>>,
     mut effect_properties_query: Query<&mut EffectProperties>,
 ) {
-    if scores.fuel_quantity > 0.0 {
-        for (action_state, children) in player.iter() {
-            let mut height = -10.0;
-            if action_state.pressed(&PlayerAction::MainThrusterBig)
-                || action_state.pressed(&PlayerAction::MainThrusterMedium)
-                || action_state.pressed(&PlayerAction::MainThrusterSmall)
-            {
-                if action_state.pressed(&PlayerAction::MainThrusterMedium) {
-                    height = -6.5;
-                } else if action_state.pressed(&PlayerAction::MainThrusterSmall) {
-                    height = -2.5;
-                }
-                for &child in children.iter() {
-                    if let Ok(effect_property) = effect_properties_query.get_mut(child) {
-                        if let Some(_property) = effect_property.get_stored("height") {
-                            EffectProperties::set_if_changed(
-                                effect_property,
-                                "height",
-                                height.into(),
-                            );
-                        }
-                    }
-                    if let Ok(mut spawner) = vertical_thruster_effect_query.get_mut(child) {
-                        spawner.reset();
-                    }
+    if scores.fuel_quantity <= 0.0 || !tick_input.is_changed() {
+        return;
+    }
+    // the strongest thruster fired on the last fixed tick sets the flame length
+    let height = if tick_input.pressed(PlayerAction::MainThrusterBig) {
+        -10.0
+    } else if tick_input.pressed(PlayerAction::MainThrusterMedium) {
+        -6.5
+    } else if tick_input.pressed(PlayerAction::MainThrusterSmall) {
+        -2.5
+    } else {
+        return;
+    };
+    for children in player.iter() {
+        for &child in children.iter() {
+            if let Ok(effect_property) = effect_properties_query.get_mut(child) {
+                if let Some(_property) = effect_property.get_stored("height") {
+                    EffectProperties::set_if_changed(effect_property, "height", height.into());
                 }
             }
+            if let Ok(mut spawner) = vertical_thruster_effect_query.get_mut(child) {
+                spawner.reset();
+            }
         }
     }
 }
 
 fn update_left_thrust_particles_system(
-    scores: ResMut<Scores>,
-    player: Query<(&ActionState<PlayerAction>, &Children), Changed<ActionState<PlayerAction>>>,
+    scores: Res<Scores>,
+    tick_input: Res<ThrusterTickInput>,
+    player: Query<&Children, With<Player>>,
     mut exhaust_effect: Query<&mut EffectSpawner, With<LeftHorizontalThrusterEffect>>,
 ) {
-    if scores.fuel_quantity > 0.0 {
-        for (action_state, children) in player.iter() {
-            if action_state.pressed(&PlayerAction::LeftThruster) {
-                for &child in children.iter() {
-                    if let Ok(mut spawner) = exhaust_effect.get_mut(child) {
-                        spawner.reset();
-                    }
-                }
+    if scores.fuel_quantity <= 0.0
+        || !tick_input.is_changed()
+        || !tick_input.pressed(PlayerAction::LeftThruster)
+    {
+        return;
+    }
+    for children in player.iter() {
+        for &child in children.iter() {
+            if let Ok(mut spawner) = exhaust_effect.get_mut(child) {
+                spawner.reset();
             }
         }
     }
 }
 
 fn update_right_thrust_particles_system(
-    scores: ResMut<Scores>,
-    player: Query<(&ActionState<PlayerAction>, &Children), Changed<ActionState<PlayerAction>>>,
+    scores: Res<Scores>,
+    tick_input: Res<ThrusterTickInput>,
+    player: Query<&Children, With<Player>>,
     mut exhaust_effect: Query<&mut EffectSpawner, With<RightHorizontalThrusterEffect>>,
 ) {
-    if scores.fuel_quantity > 0.0 {
-        for (action_state, children) in player.iter() {
-            if action_state.pressed(&PlayerAction::RightThruster) {
-                for &child in children.iter() {
-                    if let Ok(mut spawner) = exhaust_effect.get_mut(child) {
-                        spawner.reset();
-                    }
-                }
+    if scores.fuel_quantity <= 0.0
+        || !tick_input.is_changed()
+        || !tick_input.pressed(PlayerAction::RightThruster)
+    {
+        return;
+    }
+    for children in player.iter() {
+        for &child in children.iter() {
+            if let Ok(mut spawner) = exhaust_effect.get_mut(child) {
+                spawner.reset();
             }
         }
     }
@@ -0,0 +1,166 @@
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+use bevy::window::PrimaryWindow;
+use bevy_persistent::Persistent;
+use leafwing_input_manager::prelude::*;
+
+use crate::asset_loader::UiAssets;
+use crate::controls::KeyBindings;
+use crate::menu::{MenuAction, DARK_GREY_COLOR, GREY_COLOR};
+use crate::spaceship::{Player, PlayerAction};
+use crate::state::{AppState, GameState};
+
+const SIDE_REGION_WIDTH: f32 = 0.2;
+
+pub struct PointerControlsPlugin;
+
+impl Plugin for PointerControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(AppState::Game),
+            spawn_pause_button_system.run_if(pointer_controls_enabled),
+        )
+        .add_systems(
+            PreUpdate,
+            (
+                pointer_flight_controls_system.run_if(in_state(GameState::Landing)),
+                pointer_pause_button_system.run_if(in_state(AppState::Game)),
+            )
+                .after(UiSystem::Focus)
+                .in_set(InputManagerSystem::ManualControl)
+                .run_if(pointer_controls_enabled),
+        );
+    }
+}
+
+// Conditions
+pub fn pointer_controls_enabled(key_bindings: Option<Res<Persistent<KeyBindings>>>) -> bool {
+    key_bindings.is_some_and(|key_bindings| key_bindings.pointer_controls)
+}
+
+// Systems
+fn spawn_pause_button_system(mut commands: Commands, assets: Res<UiAssets>) {
+    commands
+        .spawn((
+            StateScoped(AppState::Game),
+            PauseButton,
+            ButtonBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(10.0),
+                    left: Val::Percent(50.0),
+                    width: Val::Px(60.0),
+                    height: Val::Px(40.0),
+                    margin: UiRect::left(Val::Px(-30.0)),
+                    border: UiRect::all(Val::Px(3.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                border_color: BorderColor(GREY_COLOR),
+                border_radius: BorderRadius::all(Val::Px(8.0)),
+                background_color: BackgroundColor(DARK_GREY_COLOR),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "II",
+                TextStyle {
+                    font: assets.font_vt323.clone(),
+                    font_size: 30.0,
+                    color: Color::WHITE,
+                },
+            ));
+        });
+}
+
+fn pointer_flight_controls_system(
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    windows_query: Query<&Window, With<PrimaryWindow>>,
+    mut action_state_query: Query<&mut ActionState<PlayerAction>, With<Player>>,
+) {
+    let Ok(window) = windows_query.get_single() else {
+        return;
+    };
+    let Ok(mut action_state) = action_state_query.get_single_mut() else {
+        return;
+    };
+    let window_size = Vec2::new(window.width(), window.height());
+    let mut pointer_positions: Vec<Vec2> = touches.iter().map(|touch| touch.position()).collect();
+    if mouse_buttons.pressed(MouseButton::Left) {
+        if let Some(cursor_position) = window.cursor_position() {
+            pointer_positions.push(cursor_position);
+        }
+    }
+    for pointer_position in pointer_positions {
+        if let Some(action) = pointer_action(pointer_position, window_size) {
+            action_state.press(&action);
+        }
+    }
+}
+
+fn pointer_pause_button_system(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<PauseButton>)>,
+    mut menu_action_state: ResMut<ActionState<MenuAction>>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            menu_action_state.press(&MenuAction::PauseUnpause);
+        }
+    }
+}
+
+/// Maps a pointer position in window coordinates (origin at the top left corner) to the thruster it fires.
+/// The screen sides fire the side thrusters and the lower half fires the main thruster,
+/// getting stronger as the pointer gets closer to the bottom edge.
+pub fn pointer_action(position: Vec2, window_size: Vec2) -> Option<PlayerAction> {
+    let relative_position = position / window_size;
+    if relative_position.x < SIDE_REGION_WIDTH {
+        return Some(PlayerAction::LeftThruster);
+    }
+    if relative_position.x > 1.0 - SIDE_REGION_WIDTH {
+        return Some(PlayerAction::RightThruster);
+    }
+    let depth = (relative_position.y - 0.5) * 2.0;
+    if depth < 0.0 {
+        None
+    } else if depth < 1.0 / 3.0 {
+        Some(PlayerAction::MainThrusterSmall)
+    } else if depth < 2.0 / 3.0 {
+        Some(PlayerAction::MainThrusterMedium)
+    } else {
+        Some(PlayerAction::MainThrusterBig)
+    }
+}
+
+// Components
+#[derive(Component)]
+struct PauseButton;
+
+#[test]
+fn pointer_action_regions_test() {
+    let window_size = Vec2::new(1000.0, 800.0);
+    assert_eq!(
+        pointer_action(Vec2::new(50.0, 100.0), window_size),
+        Some(PlayerAction::LeftThruster)
+    );
+    assert_eq!(
+        pointer_action(Vec2::new(950.0, 700.0), window_size),
+        Some(PlayerAction::RightThruster)
+    );
+    assert_eq!(pointer_action(Vec2::new(500.0, 100.0), window_size), None);
+    assert_eq!(
+        pointer_action(Vec2::new(500.0, 450.0), window_size),
+        Some(PlayerAction::MainThrusterSmall)
+    );
+    assert_eq!(
+        pointer_action(Vec2::new(500.0, 600.0), window_size),
+        Some(PlayerAction::MainThrusterMedium)
+    );
+    assert_eq!(
+        pointer_action(Vec2::new(500.0, 790.0), window_size),
+        Some(PlayerAction::MainThrusterBig)
+    );
+}
@@ -0,0 +1,499 @@
+//! Player profiles, each with its own folder of saved data: settings, key bindings, saved run and
+//! leaderboard. The levels a profile reached can be picked as the start level of a new game.
+use bevy::prelude::*;
+use bevy_persistent::Persistent;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::asset_loader::UiAssets;
+use crate::display::LEGACY_DISPLAY_FILE_NAME;
+use crate::game::Scores;
+use crate::localization::Localization;
+use crate::menu::{
+    confirmation, MenuCustomEvent, MenuEntry, MenuEntryAction, MenuHandler, ScreenBackStack,
+    TextInput, TextInputEvent, GREY_COLOR, SECONDARY_COLOR,
+};
+use crate::session::{end_session_on_exit_system, SessionEndedEvent};
+use crate::state::AppState;
+use crate::storage::{data_dir, load_persistent, Versioned};
+
+pub const MAX_PROFILES: usize = 6;
+const PROFILE_NAME_MAX_LENGTH: usize = 8;
+const DEFAULT_PROFILE_NAME: &str = "Pilot";
+const PROFILES_FILE_NAME: &str = "profiles.json";
+const PROFILES_DIR_NAME: &str = "profiles";
+/// Files kept in the data folder before profiles existed, moved into the first profile.
+const LEGACY_FILE_NAMES: [&str; 6] = [
+    "settings.json",
+    LEGACY_DISPLAY_FILE_NAME,
+    "bindings.json",
+    "run.json",
+    "leaderboard.json",
+    "scores.json",
+];
+/// Sent by the profile screen entries, one per profile.
+const PROFILE_EVENTS: [&str; MAX_PROFILES] = [
+    "profile.0",
+    "profile.1",
+    "profile.2",
+    "profile.3",
+    "profile.4",
+    "profile.5",
+];
+const NEW_PROFILE_EVENT: &str = "profile.new";
+/// Sent by the start level entries, one per level from level 1, the highest levels are left out.
+const START_LEVEL_EVENTS: [&str; 8] = [
+    "level.1", "level.2", "level.3", "level.4", "level.5", "level.6", "level.7", "level.8",
+];
+
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        // loaded right away, every other persisted resource is read from the active profile
+        app.insert_resource(load_profiles())
+            .add_event::<ProfileSelectedEvent>()
+            .add_systems(OnEnter(AppState::Profiles), spawn_profiles_menu_system)
+            .add_systems(
+                Update,
+                (
+                    profiles_menu_input_system,
+                    profile_name_submit_system.run_if(on_event::<TextInputEvent>()),
+                )
+                    .run_if(in_state(AppState::Profiles)),
+            )
+            .add_systems(
+                Update,
+                start_at_level_system
+                    .run_if(on_event::<MenuCustomEvent>())
+                    .run_if(in_state(AppState::Menu)),
+            )
+            .add_systems(
+                Last,
+                record_highest_level_system
+                    .run_if(on_event::<SessionEndedEvent>())
+                    .after(end_session_on_exit_system),
+            );
+    }
+}
+
+fn load_profiles() -> Persistent<Profiles> {
+    let mut profiles = load_persistent::<Profiles>("profiles", data_dir(), PROFILES_FILE_NAME);
+    if profiles.profiles.is_empty() {
+        let mut first_profiles = Profiles::clone(&profiles);
+        first_profiles.add(DEFAULT_PROFILE_NAME.into());
+        move_legacy_files(&first_profiles.active_dir());
+        if let Err(error) = profiles.set(first_profiles) {
+            error!("failed to create the first profile: {}", error);
+        }
+    }
+    profiles
+}
+
+/// Moves the files saved before profiles existed into the first profile folder.
+fn move_legacy_files(profile_dir: &Path) {
+    for file_name in LEGACY_FILE_NAMES {
+        let legacy_path = data_dir().join(file_name);
+        if !legacy_path.exists() {
+            continue;
+        }
+        let moved = fs::create_dir_all(profile_dir)
+            .and_then(|_| fs::rename(&legacy_path, profile_dir.join(file_name)));
+        match moved {
+            Ok(()) => info!("{} moved into {}", file_name, profile_dir.display()),
+            Err(error) => error!("failed to move {} into the profile: {}", file_name, error),
+        }
+    }
+}
+
+// Systems
+fn spawn_profiles_menu_system(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    localization: Res<Localization>,
+    profiles: Res<Persistent<Profiles>>,
+    back_stack: Res<ScreenBackStack>,
+) {
+    spawn_profiles_menu(
+        &mut commands,
+        &assets,
+        &localization,
+        &profiles,
+        back_stack.0.is_empty(),
+    );
+}
+
+/// Lists the profiles, at startup the last entry and Escape exit the game instead of going back.
+fn spawn_profiles_menu(
+    commands: &mut Commands,
+    assets: &UiAssets,
+    localization: &Localization,
+    profiles: &Profiles,
+    at_startup: bool,
+) {
+    let mut entries: Vec<MenuEntry> = profiles
+        .profiles
+        .iter()
+        .zip(PROFILE_EVENTS)
+        .map(|(profile, event)| {
+            MenuEntry::new(
+                localization.format(
+                    "profile.entry",
+                    &[&profile.name, profile.highest_level.to_string().as_str()],
+                ),
+                MenuEntryAction::Custom(event),
+            )
+        })
+        .collect();
+    entries.push(
+        MenuEntry::new(
+            localization.text("profile.new"),
+            MenuEntryAction::Custom(NEW_PROFILE_EVENT),
+        )
+        .enabled(profiles.profiles.len() < MAX_PROFILES),
+    );
+    let escape_action = if at_startup {
+        confirmation("menu.confirm_exit", MenuEntryAction::Quit, localization)
+    } else {
+        MenuEntryAction::Back
+    };
+    entries.push(MenuEntry::new(
+        localization.text(if at_startup { "menu.exit" } else { "menu.menu" }),
+        escape_action.clone(),
+    ));
+    let entity = MenuHandler {
+        main_text: localization.text("profile.title").into(),
+        main_text_color: SECONDARY_COLOR,
+        main_text_blink: false,
+        selected_id: profiles.active as i32,
+        entries,
+        escape_action: Some(escape_action),
+        ..default()
+    }
+    .spawn(commands, assets.font_kenvector.clone());
+    commands
+        .entity(entity)
+        .insert((ProfilesMenu, StateScoped(AppState::Profiles)));
+}
+
+fn profiles_menu_input_system(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    localization: Res<Localization>,
+    mut menu_events: EventReader<MenuCustomEvent>,
+    menu_query: Query<Entity, With<ProfilesMenu>>,
+    mut profiles: ResMut<Persistent<Profiles>>,
+    mut back_stack: ResMut<ScreenBackStack>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut profile_selected_events: EventWriter<ProfileSelectedEvent>,
+) {
+    for event in menu_events.read() {
+        if event.0 == NEW_PROFILE_EVENT {
+            for entity in menu_query.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+            spawn_profile_name_entry(&mut commands, &assets, &localization);
+        } else if let Some(index) = PROFILE_EVENTS.iter().position(|id| *id == event.0) {
+            if index != profiles.active {
+                if let Err(error) = profiles.update(|profiles| profiles.active = index) {
+                    error!("failed to select the profile: {}", error);
+                }
+                profile_selected_events.send(ProfileSelectedEvent);
+            }
+            back_stack.0.clear();
+            next_app_state.set(AppState::Menu);
+        }
+    }
+}
+
+fn start_at_level_system(
+    mut custom_events: EventReader<MenuCustomEvent>,
+    mut scores: ResMut<Scores>,
+    mut back_stack: ResMut<ScreenBackStack>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    let Some(index) = custom_events
+        .read()
+        .find_map(|MenuCustomEvent(id)| START_LEVEL_EVENTS.iter().position(|event| event == id))
+    else {
+        return;
+    };
+    scores.start_at_level(index as u32 + 1);
+    back_stack.0.clear();
+    next_app_state.set(AppState::Game);
+}
+
+/// Main menu Play entry, starting right away until the active profile has reached a higher level,
+/// then picking the start level among the unlocked ones first.
+pub fn play_action(profiles: &Profiles, localization: &Localization) -> MenuEntryAction {
+    let unlocked_levels = profiles
+        .active_profile()
+        .map_or(1, |profile| profile.highest_level as usize)
+        .min(START_LEVEL_EVENTS.len());
+    if unlocked_levels <= 1 {
+        return MenuEntryAction::StartGame;
+    }
+    let mut entries: Vec<MenuEntry> = START_LEVEL_EVENTS[..unlocked_levels]
+        .iter()
+        .enumerate()
+        .map(|(index, event)| {
+            MenuEntry::new(
+                localization.format("profile.level", &[&(index + 1).to_string()]),
+                MenuEntryAction::Custom(*event),
+            )
+        })
+        .collect();
+    entries.push(MenuEntry::new(
+        localization.text("menu.menu"),
+        MenuEntryAction::Back,
+    ));
+    MenuEntryAction::Submenu(Box::new(MenuHandler {
+        main_text: localization.text("profile.start_level").into(),
+        main_text_color: SECONDARY_COLOR,
+        main_text_blink: false,
+        selected_id: 0,
+        entries,
+        escape_action: Some(MenuEntryAction::Back),
+        ..default()
+    }))
+}
+
+fn spawn_profile_name_entry(
+    commands: &mut Commands,
+    assets: &UiAssets,
+    localization: &Localization,
+) {
+    let text_style = |font_size: f32, color: Color| TextStyle {
+        font: assets.font_kenvector.clone(),
+        font_size,
+        color,
+    };
+    let name_input = TextInput::new("", PROFILE_NAME_MAX_LENGTH);
+    commands
+        .spawn((
+            StateScoped(AppState::Profiles),
+            ProfileNameScreen,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(20.0),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                localization.text("profile.new"),
+                text_style(80.0, SECONDARY_COLOR),
+            ));
+            parent.spawn(TextBundle::from_section(
+                localization.text("profile.enter_name"),
+                text_style(30.0, GREY_COLOR),
+            ));
+            parent.spawn((
+                ProfileNameText,
+                TextBundle::from_section(name_input.label(), text_style(50.0, Color::WHITE)),
+                name_input,
+            ));
+            parent.spawn(TextBundle::from_section(
+                localization.text("profile.confirm_hint"),
+                text_style(20.0, GREY_COLOR),
+            ));
+        });
+}
+
+/// Enter creates the profile and selects it, Escape or an empty name go back to the profiles.
+fn profile_name_submit_system(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    localization: Res<Localization>,
+    screen_query: Query<Entity, With<ProfileNameScreen>>,
+    name_text_query: Query<(), With<ProfileNameText>>,
+    mut text_input_events: EventReader<TextInputEvent>,
+    mut profiles: ResMut<Persistent<Profiles>>,
+    back_stack: Res<ScreenBackStack>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut profile_selected_events: EventWriter<ProfileSelectedEvent>,
+) {
+    let Some(event) = text_input_events
+        .read()
+        .find(|event| name_text_query.contains(event.entity))
+    else {
+        return;
+    };
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if event.submitted && !event.value.is_empty() {
+        let name = event.value.clone();
+        if let Err(error) = profiles.update(|profiles| {
+            profiles.add(name.clone());
+        }) {
+            error!("failed to create the profile: {}", error);
+        }
+        profile_selected_events.send(ProfileSelectedEvent);
+        next_app_state.set(AppState::Menu);
+    } else {
+        spawn_profiles_menu(
+            &mut commands,
+            &assets,
+            &localization,
+            &profiles,
+            back_stack.0.is_empty(),
+        );
+    }
+}
+
+/// Unlocks the levels reached by the active profile.
+fn record_highest_level_system(
+    mut session_ended_events: EventReader<SessionEndedEvent>,
+    mut profiles: ResMut<Persistent<Profiles>>,
+) {
+    for event in session_ended_events.read() {
+        let reached = profiles
+            .active_profile()
+            .is_some_and(|profile| event.level > profile.highest_level);
+        if event.demo || !reached {
+            continue;
+        }
+        let level = event.level;
+        if let Err(error) = profiles.update(|profiles| {
+            if let Some(profile) = profiles.profiles.get_mut(profiles.active) {
+                profile.highest_level = level;
+            }
+        }) {
+            error!("failed to update the profile: {}", error);
+        }
+    }
+}
+
+// Events
+/// Sent when another profile becomes active, its saved data is loaded again.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ProfileSelectedEvent;
+
+// Resources
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Profiles {
+    #[serde(default)]
+    pub version: u32,
+    pub profiles: Vec<Profile>,
+    /// Index of the profile in use
+    pub active: usize,
+}
+
+impl Default for Profiles {
+    fn default() -> Self {
+        Self {
+            version: Self::VERSION,
+            profiles: vec![],
+            active: 0,
+        }
+    }
+}
+
+impl Versioned for Profiles {
+    const VERSION: u32 = 1;
+}
+
+impl Profiles {
+    pub fn active_profile(&self) -> Option<&Profile> {
+        self.profiles.get(self.active)
+    }
+
+    /// Folder of the active profile saved data.
+    pub fn active_dir(&self) -> PathBuf {
+        match self.active_profile() {
+            Some(profile) => data_dir().join(PROFILES_DIR_NAME).join(&profile.folder),
+            None => data_dir().to_path_buf(),
+        }
+    }
+
+    /// Adds a profile in a folder of its own and makes it the active one.
+    pub fn add(&mut self, name: String) {
+        let folder_name = |number: usize| format!("pilot-{}", number);
+        let mut number = 1;
+        while self
+            .profiles
+            .iter()
+            .any(|profile| profile.folder == folder_name(number))
+        {
+            number += 1;
+        }
+        let folder = folder_name(number);
+        self.profiles.push(Profile {
+            name,
+            folder,
+            highest_level: 1,
+        });
+        self.active = self.profiles.len() - 1;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    /// Folder of the profile saved data, inside the profiles folder
+    pub folder: String,
+    /// Highest level reached, levels up to it can be picked as start level
+    pub highest_level: u32,
+}
+
+// Components
+#[derive(Component, Debug)]
+struct ProfilesMenu;
+
+#[derive(Component, Debug)]
+struct ProfileNameScreen;
+
+#[derive(Component, Debug)]
+struct ProfileNameText;
+
+#[test]
+fn profiles_test() {
+    let mut profiles = Profiles::default();
+    assert_eq!(profiles.active_dir(), data_dir());
+    profiles.add("ADA".into());
+    profiles.add("BOB".into());
+    assert_eq!(profiles.active, 1);
+    assert_eq!(profiles.active_profile().unwrap().name, "BOB");
+    assert_eq!(
+        profiles.active_dir(),
+        data_dir().join(PROFILES_DIR_NAME).join("pilot-2")
+    );
+    profiles.profiles.remove(0);
+    profiles.add("CY".into());
+    assert_eq!(profiles.profiles[1].folder, "pilot-1");
+}
+
+#[test]
+fn play_action_test() {
+    let localization = Localization::new(crate::settings::Language::English);
+    let mut profiles = Profiles::default();
+    profiles.add("ADA".into());
+    assert_eq!(
+        play_action(&profiles, &localization),
+        MenuEntryAction::StartGame
+    );
+    profiles.profiles[0].highest_level = 3;
+    let MenuEntryAction::Submenu(menu) = play_action(&profiles, &localization) else {
+        panic!("the unlocked levels are not offered");
+    };
+    // three levels and back
+    assert_eq!(menu.entries.len(), 4);
+    assert_eq!(menu.entries[2].label, "Level 3");
+    assert_eq!(
+        menu.entries[2].action,
+        MenuEntryAction::Custom(START_LEVEL_EVENTS[2])
+    );
+    let mut scores = Scores::default();
+    scores.start_at_level(3);
+    assert_eq!(scores.level, 3);
+    assert!((scores.gravity - 1.2).abs() < f32::EPSILON);
+}
@@ -0,0 +1,166 @@
+use avian2d::prelude::{Gravity, GravityScale, LinearVelocity, ShapeHits};
+use bevy::audio::{Pitch, PitchBundle};
+use bevy::prelude::*;
+use bevy_persistent::Persistent;
+use std::time::Duration;
+
+use crate::collider::{Platform, WorldBounds};
+use crate::settings::Settings;
+use crate::spaceship::Player;
+use crate::state::GameState;
+
+/// Heights announced with a beep while descending, higher pitched the closer to the ground.
+const CALLOUT_HEIGHTS: [f32; 4] = [200.0, 100.0, 50.0, 20.0];
+const CALLOUT_BASE_FREQUENCY: f32 = 440.0;
+
+pub struct RadarPlugin;
+
+impl Plugin for RadarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            play_altitude_callouts_system.run_if(in_state(GameState::Landing)),
+        );
+    }
+}
+
+// Systems
+/// Registered by the lander physics plugin, the ready to land check depends on it.
+pub fn update_radar_altimeter_system(
+    gravity: Res<Gravity>,
+    platforms_query: Query<&Platform>,
+    world_bounds_query: Query<(), With<WorldBounds>>,
+    mut spaceship_query: Query<
+        (
+            &ShapeHits,
+            &LinearVelocity,
+            &GravityScale,
+            &mut RadarAltimeter,
+        ),
+        With<Player>,
+    >,
+) {
+    for (shape_hits, linear_velocity, gravity_scale, mut radar_altimeter) in
+        spaceship_query.iter_mut()
+    {
+        radar_altimeter.contact = shape_hits.iter().next().map(|hit| {
+            let surface = if let Ok(platform) = platforms_query.get(hit.entity) {
+                RadarSurface::Platform(platform.factor)
+            } else if world_bounds_query.contains(hit.entity) {
+                RadarSurface::WorldBounds
+            } else {
+                RadarSurface::Terrain
+            };
+            RadarContact {
+                entity: hit.entity,
+                surface,
+                height: hit.time_of_impact,
+                time_to_impact: time_to_impact(
+                    hit.time_of_impact,
+                    linear_velocity.y,
+                    gravity.0.y.abs() * gravity_scale.0,
+                ),
+            }
+        });
+    }
+}
+
+fn play_altitude_callouts_system(
+    mut commands: Commands,
+    mut pitch_assets: ResMut<Assets<Pitch>>,
+    settings: Res<Persistent<Settings>>,
+    radar_query: Query<&RadarAltimeter, With<Player>>,
+    mut previous_height: Local<Option<f32>>,
+) {
+    let Ok(radar_altimeter) = radar_query.get_single() else {
+        return;
+    };
+    let height = radar_altimeter.height();
+    if let Some(index) = crossed_callout(*previous_height, height) {
+        commands.spawn(PitchBundle {
+            source: pitch_assets.add(Pitch::new(
+                CALLOUT_BASE_FREQUENCY * (1.0 + index as f32 * 0.25),
+                Duration::from_millis(120),
+            )),
+            settings: PlaybackSettings::DESPAWN.with_volume(settings.sfx_volume()),
+        });
+    }
+    *previous_height = height;
+}
+
+/// Seconds until the lander falls `height` units, starting at `vertical_velocity` and accelerated
+/// by `gravity` downwards. None when it never gets there.
+pub fn time_to_impact(height: f32, vertical_velocity: f32, gravity: f32) -> Option<f32> {
+    if height <= 0.0 {
+        return Some(0.0);
+    }
+    if gravity.abs() < f32::EPSILON {
+        return (vertical_velocity < 0.0).then(|| height / -vertical_velocity);
+    }
+    // height + vertical_velocity * t - gravity * t² / 2 = 0
+    let discriminant = vertical_velocity * vertical_velocity + 2.0 * gravity * height;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let seconds = (vertical_velocity + discriminant.sqrt()) / gravity;
+    (seconds >= 0.0).then_some(seconds)
+}
+
+/// Index of the callout height crossed while descending from `previous_height` to `height`.
+fn crossed_callout(previous_height: Option<f32>, height: Option<f32>) -> Option<usize> {
+    let (previous_height, height) = (previous_height?, height?);
+    CALLOUT_HEIGHTS
+        .iter()
+        .rposition(|callout| previous_height > *callout && height <= *callout)
+}
+
+// Components
+/// Ground radar reading from the lander shape caster, updated every fixed tick while landing.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct RadarAltimeter {
+    pub contact: Option<RadarContact>,
+}
+
+impl RadarAltimeter {
+    pub fn height(&self) -> Option<f32> {
+        self.contact.map(|contact| contact.height)
+    }
+
+    /// Platform factor right below the lander, if any.
+    pub fn platform_below(&self) -> Option<i32> {
+        match self.contact?.surface {
+            RadarSurface::Platform(factor) => Some(factor),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadarContact {
+    pub entity: Entity,
+    pub surface: RadarSurface,
+    /// Gap between the lander collider and the surface below
+    pub height: f32,
+    pub time_to_impact: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadarSurface {
+    Terrain,
+    Platform(i32),
+    WorldBounds,
+}
+
+#[test]
+fn time_to_impact_test() {
+    // free fall from rest, h = g * t² / 2
+    assert_eq!(time_to_impact(29.0, 0.0, 58.0), Some(1.0));
+    // climbing first and falling back afterwards
+    assert_eq!(time_to_impact(0.0, 50.0, 58.0), Some(0.0));
+    assert!(time_to_impact(10.0, 50.0, 58.0).unwrap() > 50.0 / 58.0);
+    // no gravity at all
+    assert_eq!(time_to_impact(100.0, -50.0, 0.0), Some(2.0));
+    assert_eq!(time_to_impact(100.0, 50.0, 0.0), None);
+    assert_eq!(crossed_callout(Some(120.0), Some(95.0)), Some(1));
+    assert_eq!(crossed_callout(Some(95.0), Some(120.0)), None);
+}
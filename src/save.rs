@@ -0,0 +1,241 @@
+use bevy::prelude::*;
+use bevy_persistent::Persistent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::attract::AttractMode;
+use crate::game::Scores;
+use crate::menu::MenuCustomEvent;
+use crate::profile::{ProfileSelectedEvent, Profiles};
+use crate::session::{end_session_system, GameSession};
+use crate::state::{AppState, GameState, LaunchConditions};
+use crate::storage::{load_persistent, Versioned};
+
+/// Sent by the main menu "Continue" entry.
+pub const CONTINUE_RUN_EVENT: &str = "run.continue";
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, persist_saved_run_system)
+            .add_systems(
+                PostUpdate,
+                persist_saved_run_system.run_if(on_event::<ProfileSelectedEvent>()),
+            )
+            .add_systems(OnEnter(GameState::Setup), save_run_system)
+            .add_systems(OnEnter(GameState::GameOver), clear_saved_run_system)
+            .add_systems(
+                OnExit(AppState::Game),
+                save_run_on_leave_system.before(end_session_system),
+            )
+            .add_systems(
+                Update,
+                continue_saved_run_system.run_if(in_state(AppState::Menu)),
+            )
+            .add_systems(
+                Last,
+                save_run_on_exit_system.run_if(in_state(AppState::Game)),
+            );
+    }
+}
+
+// Systems
+fn persist_saved_run_system(mut commands: Commands, profiles: Res<Persistent<Profiles>>) {
+    commands.insert_resource(load_persistent::<SavedRun>(
+        "saved run",
+        &profiles.active_dir(),
+        "run.json",
+    ));
+}
+
+/// Saves the run before every landing attempt, the demo never overwrites it.
+fn save_run_system(
+    attract_mode: Res<AttractMode>,
+    scores: Res<Scores>,
+    launch_conditions: Res<LaunchConditions>,
+    mut saved_run: ResMut<Persistent<SavedRun>>,
+) {
+    if attract_mode.active {
+        return;
+    }
+    if let Err(error) = saved_run.set(SavedRun::new(&scores, &launch_conditions)) {
+        error!("failed to save the run: {}", error);
+    }
+}
+
+fn clear_saved_run_system(
+    attract_mode: Res<AttractMode>,
+    mut saved_run: ResMut<Persistent<SavedRun>>,
+) {
+    if attract_mode.active || saved_run.run.is_none() {
+        return;
+    }
+    if let Err(error) = saved_run.set(SavedRun::default()) {
+        error!("failed to clear the saved run: {}", error);
+    }
+}
+
+/// Saves the run left from the pause menu with the fuel spent so far, so leaving a bad descent
+/// doesn't give the fuel back. Runs that ended in game over were already cleared.
+fn save_run_on_leave_system(
+    attract_mode: Res<AttractMode>,
+    scores: Res<Scores>,
+    launch_conditions: Res<LaunchConditions>,
+    mut saved_run: ResMut<Persistent<SavedRun>>,
+) {
+    if attract_mode.active || saved_run.run.is_none() {
+        return;
+    }
+    if let Err(error) = saved_run.set(SavedRun::new(&scores, &launch_conditions)) {
+        error!("failed to save the run: {}", error);
+    }
+}
+
+fn continue_saved_run_system(
+    mut commands: Commands,
+    mut custom_events: EventReader<MenuCustomEvent>,
+    saved_run: Res<Persistent<SavedRun>>,
+    mut scores: ResMut<Scores>,
+    mut launch_conditions: ResMut<LaunchConditions>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    if !custom_events
+        .read()
+        .any(|MenuCustomEvent(id)| *id == CONTINUE_RUN_EVENT)
+    {
+        return;
+    }
+    let Some(run) = saved_run.run else {
+        return;
+    };
+    run.restore(&mut scores, &mut launch_conditions);
+    commands.insert_resource(GameSession::continued());
+    info!("continuing saved run at level {}", run.level);
+    next_app_state.set(AppState::Game);
+}
+
+/// Saves the run in progress when the game is closed, whatever the way it is closed.
+fn save_run_on_exit_system(
+    mut exit_events: EventReader<AppExit>,
+    game_state: Option<Res<State<GameState>>>,
+    attract_mode: Res<AttractMode>,
+    scores: Res<Scores>,
+    launch_conditions: Res<LaunchConditions>,
+    mut saved_run: ResMut<Persistent<SavedRun>>,
+) {
+    if exit_events.read().count() == 0 || attract_mode.active {
+        return;
+    }
+    if game_state.is_some_and(|game_state| *game_state.get() == GameState::GameOver) {
+        return;
+    }
+    match saved_run.set(SavedRun::new(&scores, &launch_conditions)) {
+        Ok(()) => info!("run saved before exit"),
+        Err(error) => error!("failed to save the run before exit: {}", error),
+    }
+}
+
+// Resources
+/// Last run left unfinished, persisted next to the scores. Only one run is kept.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SavedRun {
+    #[serde(default)]
+    pub version: u32,
+    pub run: Option<RunSnapshot>,
+}
+
+impl Default for SavedRun {
+    fn default() -> Self {
+        Self {
+            version: Self::VERSION,
+            run: None,
+        }
+    }
+}
+
+impl Versioned for SavedRun {
+    const VERSION: u32 = 1;
+
+    fn migrate(mut value: Value, version: u32) -> Value {
+        // version 1 added the level and the launch conditions seed
+        if version == 0 {
+            if let Some(run) = value.get_mut("run").and_then(Value::as_object_mut) {
+                run.entry("level").or_insert(1.into());
+                run.entry("seed").or_insert(Value::Null);
+            }
+        }
+        value
+    }
+}
+
+impl SavedRun {
+    pub fn new(scores: &Scores, launch_conditions: &LaunchConditions) -> Self {
+        Self {
+            version: Self::VERSION,
+            run: Some(RunSnapshot {
+                score: scores.score,
+                fuel_quantity: scores.fuel_quantity.max(0.0),
+                gravity: scores.gravity,
+                level: scores.level,
+                seed: launch_conditions.seed,
+            }),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RunSnapshot {
+    pub score: i32,
+    pub fuel_quantity: f32,
+    pub gravity: f32,
+    pub level: u32,
+    /// Seed of the launch conditions, None for the default ones
+    pub seed: Option<u64>,
+}
+
+impl RunSnapshot {
+    pub fn restore(&self, scores: &mut Scores, launch_conditions: &mut LaunchConditions) {
+        scores.score = self.score;
+        scores.fuel_quantity = self.fuel_quantity;
+        scores.gravity = self.gravity;
+        scores.level = self.level;
+        *launch_conditions = match self.seed {
+            Some(seed) => LaunchConditions::from_seed(seed),
+            None => LaunchConditions::default(),
+        };
+    }
+}
+
+#[test]
+fn saved_run_test() {
+    let mut scores = Scores {
+        score: 1500,
+        hi_score: 2000,
+        fuel_quantity: -10.0,
+        gravity: 1.3,
+        level: 4,
+    };
+    let launch_conditions = LaunchConditions::from_seed(7);
+    let saved_run = SavedRun::new(&scores, &launch_conditions);
+    let json = serde_json::to_string(&saved_run).unwrap();
+    let run = serde_json::from_str::<SavedRun>(&json)
+        .unwrap()
+        .run
+        .unwrap();
+    scores.score = 0;
+    let mut restored_launch_conditions = LaunchConditions::default();
+    run.restore(&mut scores, &mut restored_launch_conditions);
+    assert_eq!(scores.score, 1500);
+    assert_eq!(scores.fuel_quantity, 0.0);
+    assert_eq!(scores.level, 4);
+    assert_eq!(restored_launch_conditions, launch_conditions);
+    // runs saved before levels and seeds were saved too
+    let json = serde_json::json!({
+        "run": { "score": 10, "fuel_quantity": 500.0, "gravity": 1.1 }
+    });
+    let value = SavedRun::migrate(json, 0);
+    let saved_run: SavedRun = serde_json::from_value(value).unwrap();
+    assert_eq!(saved_run.run.unwrap().level, 1);
+    assert_eq!(saved_run.run.unwrap().seed, None);
+}
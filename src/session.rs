@@ -0,0 +1,185 @@
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::attract::AttractMode;
+use crate::autopilot::Autopilot;
+use crate::game::Scores;
+use crate::state::{AppState, GameState, LaunchConditions, TenSecondsTimer};
+
+pub struct SessionPlugin;
+
+impl Plugin for SessionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SessionStartedEvent>()
+            .add_event::<SessionEndedEvent>()
+            .add_systems(OnEnter(AppState::Game), start_session_system)
+            .add_systems(OnExit(AppState::Game), end_session_system)
+            .add_systems(
+                Update,
+                tick_flight_time_system.run_if(in_state(GameState::Landing)),
+            )
+            .add_systems(
+                Last,
+                end_session_on_exit_system.run_if(in_state(AppState::Game)),
+            );
+    }
+}
+
+// Systems
+/// Starts a new session unless one was prepared before entering the game, like a continued run.
+fn start_session_system(
+    mut commands: Commands,
+    session: Option<Res<GameSession>>,
+    attract_mode: Res<AttractMode>,
+    mut autopilot: ResMut<Autopilot>,
+    mut session_started_events: EventWriter<SessionStartedEvent>,
+) {
+    let session = session.map_or_else(GameSession::default, |session| session.clone());
+    // the attract mode demo turns the autopilot on before starting
+    if !attract_mode.active {
+        *autopilot = Autopilot::default();
+    }
+    session_started_events.send(SessionStartedEvent {
+        continued: session.continued,
+        demo: attract_mode.active,
+    });
+    commands.insert_resource(session);
+}
+
+/// Tears the session down, so the next game starts from the initial score, fuel and gravity.
+pub fn end_session_system(
+    mut commands: Commands,
+    session: Option<Res<GameSession>>,
+    attract_mode: Res<AttractMode>,
+    mut scores: ResMut<Scores>,
+    mut launch_conditions: ResMut<LaunchConditions>,
+    mut autopilot: ResMut<Autopilot>,
+    mut session_ended_events: EventWriter<SessionEndedEvent>,
+) {
+    session_ended_events.send(SessionEndedEvent::new(
+        session.as_deref(),
+        &scores,
+        attract_mode.active,
+    ));
+    commands.remove_resource::<GameSession>();
+    commands.remove_resource::<TenSecondsTimer>();
+    *scores = Scores::default();
+    *launch_conditions = LaunchConditions::default();
+    *autopilot = Autopilot::default();
+}
+
+/// Closing the game ends the session too, so its flight time and fuel burned are not lost.
+pub fn end_session_on_exit_system(
+    mut exit_events: EventReader<AppExit>,
+    session: Option<Res<GameSession>>,
+    attract_mode: Res<AttractMode>,
+    scores: Res<Scores>,
+    mut session_ended_events: EventWriter<SessionEndedEvent>,
+) {
+    if exit_events.read().count() == 0 {
+        return;
+    }
+    session_ended_events.send(SessionEndedEvent::new(
+        session.as_deref(),
+        &scores,
+        attract_mode.active,
+    ));
+}
+
+fn tick_flight_time_system(time: Res<Time>, session: Option<ResMut<GameSession>>) {
+    if let Some(mut session) = session {
+        session.flight_time += time.delta();
+    }
+}
+
+// Events
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SessionStartedEvent {
+    /// A saved run was continued instead of starting from scratch
+    pub continued: bool,
+    /// Played by the attract mode demo
+    pub demo: bool,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SessionEndedEvent {
+    pub score: i32,
+    pub level: u32,
+    pub flight_time: Duration,
+    pub demo: bool,
+}
+
+impl SessionEndedEvent {
+    fn new(session: Option<&GameSession>, scores: &Scores, demo: bool) -> Self {
+        Self {
+            score: scores.score,
+            level: scores.level,
+            flight_time: session.map_or(Duration::ZERO, |session| session.flight_time),
+            demo,
+        }
+    }
+}
+
+// Resources
+/// Game in progress, from "Play" or "Continue" until the game is left.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct GameSession {
+    pub continued: bool,
+    /// Time spent flying, pauses and landed screens aside
+    pub flight_time: Duration,
+    /// The autopilot flew at some point, the run is left out of the leaderboard
+    pub autopilot_used: bool,
+}
+
+impl GameSession {
+    pub fn continued() -> Self {
+        Self {
+            continued: true,
+            ..default()
+        }
+    }
+}
+
+#[test]
+fn session_lifecycle_test() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        bevy::state::app::StatesPlugin,
+        SessionPlugin,
+    ))
+    .init_state::<AppState>()
+    .add_sub_state::<GameState>()
+    .init_resource::<AttractMode>()
+    .init_resource::<Autopilot>()
+    .init_resource::<Scores>()
+    .init_resource::<LaunchConditions>();
+    app.world_mut()
+        .resource_mut::<NextState<AppState>>()
+        .set(AppState::Game);
+    app.update();
+    assert!(app.world().contains_resource::<GameSession>());
+    // play a bit
+    app.insert_resource(TenSecondsTimer(Timer::from_seconds(10.0, TimerMode::Once)));
+    app.world_mut().resource_mut::<Scores>().score = 250;
+    app.world_mut().resource_mut::<Scores>().level = 3;
+    app.insert_resource(LaunchConditions::from_seed(7));
+    app.world_mut()
+        .resource_mut::<NextState<AppState>>()
+        .set(AppState::Menu);
+    app.update();
+    assert!(!app.world().contains_resource::<GameSession>());
+    assert!(!app.world().contains_resource::<TenSecondsTimer>());
+    assert_eq!(app.world().resource::<Scores>().score, 0);
+    assert_eq!(app.world().resource::<Scores>().level, 1);
+    assert_eq!(
+        *app.world().resource::<LaunchConditions>(),
+        LaunchConditions::default()
+    );
+    let events = app.world().resource::<Events<SessionEndedEvent>>();
+    let ended: Vec<_> = events.get_reader().read(events).copied().collect();
+    assert_eq!(ended.len(), 1);
+    assert_eq!(ended[0].score, 250);
+    assert_eq!(ended[0].level, 3);
+    assert!(!ended[0].demo);
+}
@@ -0,0 +1,573 @@
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use bevy_persistent::Persistent;
+use serde::{Deserialize, Serialize};
+
+use crate::accessibility::AccessibilitySettings;
+use crate::asset_loader::UiAssets;
+use crate::assists::Assists;
+use crate::audio::{MusicBeginSoundEffect, MusicPlayingSoundEffect};
+use crate::display::{import_legacy_display_settings, DisplaySettings};
+use crate::landing_rules::LandingRules;
+use crate::localization::{Localization, LocalizedText};
+use crate::menu::{
+    spawn_pause_menu_handler, ButtonId, MenuCustomEvent, MenuEntry, MenuEntryAction, MenuHandler,
+    SECONDARY_COLOR,
+};
+use crate::profile::{ProfileSelectedEvent, Profiles};
+use crate::state::{AppState, GameState};
+use crate::storage::{data_dir, load_persistent, Versioned};
+use crate::{WINDOW_HEIGHT, WINDOW_WIDTH};
+
+const SETTINGS_ENTRIES: [SettingsEntry; 16] = [
+    SettingsEntry::MusicVolume,
+    SettingsEntry::SfxVolume,
+    SettingsEntry::Fullscreen,
+    SettingsEntry::WindowSize,
+    SettingsEntry::Vsync,
+    SettingsEntry::TrajectoryAssist,
+    SettingsEntry::Difficulty,
+    SettingsEntry::Language,
+    SettingsEntry::Palette,
+    SettingsEntry::HighContrast,
+    SettingsEntry::TextScale,
+    SettingsEntry::Blinking,
+    SettingsEntry::BackgroundRotation,
+    SettingsEntry::CameraSnaps,
+    SettingsEntry::SafeBandCue,
+    SettingsEntry::Back,
+];
+/// Windowed sizes offered by the settings menu, all of them with the play area aspect ratio.
+const WINDOW_SCALES: [f32; 4] = [1.0, 1.25, 1.5, 1.875];
+const VOLUME_STEP: f32 = 0.1;
+/// Sent by the pause menu to open the settings over it.
+pub const OPEN_SETTINGS_EVENT: &str = "settings.open";
+/// Sent by the settings opened from the pause menu to go back to it.
+const CLOSE_SETTINGS_EVENT: &str = "settings.close";
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreStartup, persist_settings_system)
+            .add_systems(
+                PostUpdate,
+                persist_settings_system.run_if(on_event::<ProfileSelectedEvent>()),
+            )
+            .add_systems(OnEnter(AppState::Settings), spawn_settings_screen_system)
+            .add_systems(
+                Update,
+                (
+                    settings_menu_input_system,
+                    update_settings_menu_texts_system,
+                    (apply_gameplay_settings_system, apply_music_volume_system)
+                        .run_if(resource_changed::<Persistent<Settings>>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+// Systems
+fn persist_settings_system(mut commands: Commands, profiles: Res<Persistent<Profiles>>) {
+    let profile_dir = profiles.active_dir();
+    let mut settings = load_persistent::<Settings>("settings", &profile_dir, "settings.json");
+    for dir in [profile_dir.as_path(), data_dir()] {
+        import_legacy_display_settings(&mut settings, dir);
+    }
+    commands.insert_resource(settings);
+}
+
+fn spawn_settings_screen_system(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    settings: Res<Persistent<Settings>>,
+    localization: Res<Localization>,
+) {
+    let entity = spawn_settings_menu(
+        &mut commands,
+        &assets,
+        &settings,
+        &localization,
+        MenuEntryAction::Back,
+    );
+    commands
+        .entity(entity)
+        .insert(StateScoped(AppState::Settings));
+}
+
+/// Spawns the settings menu, either as the settings screen or over the pause menu, the back entry
+/// and Escape run `back_action`.
+fn spawn_settings_menu(
+    commands: &mut Commands,
+    assets: &UiAssets,
+    settings: &Settings,
+    localization: &Localization,
+    back_action: MenuEntryAction,
+) -> Entity {
+    let labels: Vec<String> = SETTINGS_ENTRIES
+        .iter()
+        .map(|entry| entry.label(settings, localization))
+        .collect();
+    commands
+        .spawn((
+            SettingsMenu,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::BLACK),
+                ..default()
+            },
+            MenuHandler {
+                main_text: localization.text("settings.title").into(),
+                main_text_color: SECONDARY_COLOR,
+                main_text_blink: false,
+                entries: SETTINGS_ENTRIES
+                    .iter()
+                    .zip(labels.iter())
+                    .map(|(entry, label)| {
+                        let action = match entry {
+                            SettingsEntry::Back => back_action.clone(),
+                            entry => MenuEntryAction::Custom(entry.key()),
+                        };
+                        MenuEntry::new(label.clone(), action)
+                    })
+                    .collect(),
+                selected_id: 0,
+                escape_action: Some(back_action),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                LocalizedText("settings.title"),
+                TextBundle::from_section(
+                    localization.text("settings.title"),
+                    TextStyle {
+                        font: assets.font_kenvector.clone(),
+                        font_size: 60.0,
+                        color: SECONDARY_COLOR,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                }),
+            ));
+            for (i, label) in labels.iter().enumerate() {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(520.0),
+                                min_height: Val::Px(30.0),
+                                border: UiRect::all(Val::Px(3.0)),
+                                padding: UiRect::horizontal(Val::Px(12.0)),
+                                align_items: AlignItems::Center,
+                                margin: UiRect::all(Val::Px(2.0)),
+                                ..default()
+                            },
+                            border_radius: BorderRadius::all(Val::Px(6.0)),
+                            ..default()
+                        },
+                        ButtonId(i as i32),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            SettingsEntryText(i),
+                            TextBundle::from_section(
+                                label.clone(),
+                                TextStyle {
+                                    font: assets.font_vt323.clone(),
+                                    font_size: 24.0,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                        ));
+                    });
+            }
+        })
+        .id()
+}
+
+fn settings_menu_input_system(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    localization: Res<Localization>,
+    menus_query: Query<Entity, With<MenuHandler>>,
+    mut custom_events: EventReader<MenuCustomEvent>,
+    mut settings: ResMut<Persistent<Settings>>,
+) {
+    for MenuCustomEvent(id) in custom_events.read() {
+        match *id {
+            // the settings replace the pause menu while paused, so the flight isn't torn down
+            OPEN_SETTINGS_EVENT | CLOSE_SETTINGS_EVENT => {
+                for entity in menus_query.iter() {
+                    commands.entity(entity).despawn_recursive();
+                }
+                if *id == OPEN_SETTINGS_EVENT {
+                    let entity = spawn_settings_menu(
+                        &mut commands,
+                        &assets,
+                        &settings,
+                        &localization,
+                        MenuEntryAction::Custom(CLOSE_SETTINGS_EVENT),
+                    );
+                    commands
+                        .entity(entity)
+                        .insert(StateScoped(GameState::Paused));
+                } else {
+                    spawn_pause_menu_handler(&mut commands, &assets, &localization);
+                }
+            }
+            id => {
+                let Some(entry) = SETTINGS_ENTRIES.iter().find(|entry| entry.key() == id) else {
+                    continue;
+                };
+                if let Err(error) = settings.update(|settings| entry.change(settings)) {
+                    error!("failed to update settings: {}", error);
+                }
+            }
+        }
+    }
+}
+
+fn update_settings_menu_texts_system(
+    settings: Res<Persistent<Settings>>,
+    localization: Res<Localization>,
+    mut entry_texts_query: Query<(&SettingsEntryText, &mut Text)>,
+) {
+    if !settings.is_changed() && !localization.is_changed() {
+        return;
+    }
+    for (entry_text, mut text) in entry_texts_query.iter_mut() {
+        text.sections[0].value = SETTINGS_ENTRIES[entry_text.0].label(&settings, &localization);
+    }
+}
+
+/// Only applied when they change, so unrelated settings don't undo the assist key toggles.
+fn apply_gameplay_settings_system(
+    settings: Res<Persistent<Settings>>,
+    mut assists: ResMut<Assists>,
+    mut landing_rules: ResMut<LandingRules>,
+    mut applied: Local<Option<(bool, Difficulty)>>,
+) {
+    let gameplay_settings = (settings.trajectory_assist, settings.difficulty);
+    if *applied == Some(gameplay_settings) {
+        return;
+    }
+    *applied = Some(gameplay_settings);
+    assists.trajectory = settings.trajectory_assist;
+    *landing_rules = LandingRules::for_difficulty(settings.difficulty);
+}
+
+fn apply_music_volume_system(
+    settings: Res<Persistent<Settings>>,
+    music_sinks_query: Query<
+        &AudioSink,
+        Or<(With<MusicBeginSoundEffect>, With<MusicPlayingSoundEffect>)>,
+    >,
+) {
+    for sink in music_sinks_query.iter() {
+        sink.set_volume(settings.music_volume);
+    }
+}
+
+// Resources
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub version: u32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub display: DisplaySettings,
+    /// Trajectory assist state when the game starts, the assist key still toggles it while playing
+    pub trajectory_assist: bool,
+    pub difficulty: Difficulty,
+    pub language: Language,
+    pub accessibility: AccessibilitySettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: Self::VERSION,
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            display: DisplaySettings::default(),
+            trajectory_assist: false,
+            difficulty: Difficulty::Normal,
+            language: Language::English,
+            accessibility: AccessibilitySettings::default(),
+        }
+    }
+}
+
+impl Versioned for Settings {
+    const VERSION: u32 = 1;
+}
+
+impl Settings {
+    pub fn music_volume(&self) -> Volume {
+        Volume::new(self.music_volume)
+    }
+
+    pub fn sfx_volume(&self) -> Volume {
+        Volume::new(self.sfx_volume)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    /// Scale applied to the fastest allowed touchdown of every landing window.
+    pub fn landing_speed_scale(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.4,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.7,
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Easy,
+        }
+    }
+
+    /// Localization key of the difficulty name.
+    fn key(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "difficulty.easy",
+            Difficulty::Normal => "difficulty.normal",
+            Difficulty::Hard => "difficulty.hard",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Language {
+    #[default]
+    English,
+    Catalan,
+    Spanish,
+}
+
+impl Language {
+    fn next(&self) -> Self {
+        match self {
+            Language::English => Language::Catalan,
+            Language::Catalan => Language::Spanish,
+            Language::Spanish => Language::English,
+        }
+    }
+
+    /// Language name written in the language itself.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Catalan => "Català",
+            Language::Spanish => "Español",
+        }
+    }
+}
+
+// Components
+#[derive(Component)]
+pub struct SettingsMenu;
+
+#[derive(Component)]
+struct SettingsEntryText(usize);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum SettingsEntry {
+    MusicVolume,
+    SfxVolume,
+    Fullscreen,
+    WindowSize,
+    Vsync,
+    TrajectoryAssist,
+    Difficulty,
+    Language,
+    Palette,
+    HighContrast,
+    TextScale,
+    Blinking,
+    BackgroundRotation,
+    CameraSnaps,
+    SafeBandCue,
+    Back,
+}
+
+impl SettingsEntry {
+    /// Localization key of the entry name.
+    fn key(&self) -> &'static str {
+        match self {
+            SettingsEntry::MusicVolume => "settings.music_volume",
+            SettingsEntry::SfxVolume => "settings.sfx_volume",
+            SettingsEntry::Fullscreen => "settings.fullscreen",
+            SettingsEntry::WindowSize => "settings.window_size",
+            SettingsEntry::Vsync => "settings.vsync",
+            SettingsEntry::TrajectoryAssist => "settings.trajectory_assist",
+            SettingsEntry::Difficulty => "settings.difficulty",
+            SettingsEntry::Language => "settings.language",
+            SettingsEntry::Palette => "settings.palette",
+            SettingsEntry::HighContrast => "settings.high_contrast",
+            SettingsEntry::TextScale => "settings.text_scale",
+            SettingsEntry::Blinking => "settings.blinking",
+            SettingsEntry::BackgroundRotation => "settings.background_rotation",
+            SettingsEntry::CameraSnaps => "settings.camera_snaps",
+            SettingsEntry::SafeBandCue => "settings.safe_band_cue",
+            SettingsEntry::Back => "settings.back",
+        }
+    }
+
+    fn label(&self, settings: &Settings, localization: &Localization) -> String {
+        let name = localization.text(self.key());
+        let accessibility = &settings.accessibility;
+        let value = match self {
+            SettingsEntry::MusicVolume => format_volume(settings.music_volume),
+            SettingsEntry::SfxVolume => format_volume(settings.sfx_volume),
+            SettingsEntry::Fullscreen => localization
+                .text(on_off(settings.display.fullscreen))
+                .into(),
+            SettingsEntry::WindowSize => format!(
+                "{:.0}x{:.0}",
+                settings.display.width, settings.display.height
+            ),
+            SettingsEntry::Vsync => localization.text(on_off(settings.display.vsync)).into(),
+            SettingsEntry::TrajectoryAssist => {
+                localization.text(on_off(settings.trajectory_assist)).into()
+            }
+            SettingsEntry::Difficulty => localization.text(settings.difficulty.key()).into(),
+            SettingsEntry::Language => settings.language.name().into(),
+            SettingsEntry::Palette => localization.text(accessibility.palette.key()).into(),
+            SettingsEntry::HighContrast => localization
+                .text(on_off(accessibility.high_contrast))
+                .into(),
+            SettingsEntry::TextScale => format!("{:.0}%", accessibility.text_scale * 100.0),
+            SettingsEntry::Blinking => localization.text(on_off(accessibility.blinking)).into(),
+            SettingsEntry::BackgroundRotation => localization
+                .text(on_off(accessibility.background_rotation))
+                .into(),
+            SettingsEntry::CameraSnaps => {
+                localization.text(on_off(accessibility.camera_snaps)).into()
+            }
+            SettingsEntry::SafeBandCue => localization
+                .text(on_off(accessibility.safe_band_cue))
+                .into(),
+            SettingsEntry::Back => return name.into(),
+        };
+        format!("{:<20}{:>12}", name, value)
+    }
+
+    /// Every entry cycles through its values when accepted.
+    fn change(&self, settings: &mut Settings) {
+        match self {
+            SettingsEntry::MusicVolume => {
+                settings.music_volume = next_volume(settings.music_volume)
+            }
+            SettingsEntry::SfxVolume => settings.sfx_volume = next_volume(settings.sfx_volume),
+            SettingsEntry::Fullscreen => settings.display.fullscreen = !settings.display.fullscreen,
+            SettingsEntry::WindowSize => {
+                let scale = WINDOW_SCALES
+                    .iter()
+                    .copied()
+                    .find(|scale| WINDOW_WIDTH * scale > settings.display.width + 1.0)
+                    .unwrap_or(WINDOW_SCALES[0]);
+                settings.display.width = WINDOW_WIDTH * scale;
+                settings.display.height = WINDOW_HEIGHT * scale;
+            }
+            SettingsEntry::Vsync => settings.display.vsync = !settings.display.vsync,
+            SettingsEntry::TrajectoryAssist => {
+                settings.trajectory_assist = !settings.trajectory_assist
+            }
+            SettingsEntry::Difficulty => settings.difficulty = settings.difficulty.next(),
+            SettingsEntry::Language => settings.language = settings.language.next(),
+            SettingsEntry::Palette => {
+                settings.accessibility.palette = settings.accessibility.palette.next()
+            }
+            SettingsEntry::HighContrast => {
+                settings.accessibility.high_contrast = !settings.accessibility.high_contrast
+            }
+            SettingsEntry::TextScale => {
+                settings.accessibility.text_scale = settings.accessibility.next_text_scale()
+            }
+            SettingsEntry::Blinking => {
+                settings.accessibility.blinking = !settings.accessibility.blinking
+            }
+            SettingsEntry::BackgroundRotation => {
+                settings.accessibility.background_rotation =
+                    !settings.accessibility.background_rotation
+            }
+            SettingsEntry::CameraSnaps => {
+                settings.accessibility.camera_snaps = !settings.accessibility.camera_snaps
+            }
+            SettingsEntry::SafeBandCue => {
+                settings.accessibility.safe_band_cue = !settings.accessibility.safe_band_cue
+            }
+            SettingsEntry::Back => {}
+        }
+    }
+}
+
+/// Localization key of a switch state.
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "common.on"
+    } else {
+        "common.off"
+    }
+}
+
+fn format_volume(volume: f32) -> String {
+    format!("{:.0}%", volume * 100.0)
+}
+
+/// Next volume step, wrapping around to mute after the maximum.
+fn next_volume(volume: f32) -> f32 {
+    let steps = (volume / VOLUME_STEP).round() as i32 + 1;
+    if steps > (1.0 / VOLUME_STEP).round() as i32 {
+        0.0
+    } else {
+        steps as f32 * VOLUME_STEP
+    }
+}
+
+#[test]
+fn settings_entries_cycle_test() {
+    let mut settings = Settings::default();
+    SettingsEntry::MusicVolume.change(&mut settings);
+    assert_eq!(settings.music_volume, 0.0);
+    SettingsEntry::MusicVolume.change(&mut settings);
+    assert_eq!(settings.music_volume, 0.1);
+    SettingsEntry::WindowSize.change(&mut settings);
+    assert_eq!(settings.display.width, WINDOW_WIDTH * 1.25);
+    assert_eq!(settings.display.height, WINDOW_HEIGHT * 1.25);
+    settings.display.width = 3000.0;
+    SettingsEntry::WindowSize.change(&mut settings);
+    assert_eq!(settings.display.width, WINDOW_WIDTH);
+    SettingsEntry::Difficulty.change(&mut settings);
+    assert_eq!(settings.difficulty, Difficulty::Hard);
+    SettingsEntry::TextScale.change(&mut settings);
+    assert_eq!(settings.accessibility.text_scale, 1.25);
+    // settings saved by older versions get the missing fields from the defaults
+    let settings: Settings = serde_json::from_str(r#"{"music_volume": 0.5}"#).unwrap();
+    assert_eq!(settings.music_volume, 0.5);
+    assert_eq!(settings.language, Language::English);
+    assert!(settings.accessibility.blinking);
+}
@@ -1,14 +1,20 @@
 use avian2d::prelude::*;
 use bevy::prelude::*;
 use bevy_collider_gen::avian2d::single_convex_polyline_collider_translated;
+use bevy_persistent::Persistent;
 use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::asset_loader::SceneAssets;
+use crate::controls::KeyBindings;
 use crate::game::Scores;
 use crate::movement::*;
+use crate::radar::RadarAltimeter;
 use crate::state::AppState;
 
 pub const INITIAL_SPACESHIP_POSITION: Vec3 = Vec3::new(-300.0, 300.0, 2.0);
+pub const LANDER_JUMP_IMPULSE: f32 = 4.9;
+pub const LANDER_MOVEMENT_DAMPING: f32 = 0.965;
 
 pub struct SpaceshipPlugin;
 
@@ -25,33 +31,15 @@ fn spawn_spaceship_system(
     scene_assets: Res<SceneAssets>,
     image_assets: Res<Assets<Image>>,
     scores: Res<Scores>,
+    key_bindings: Res<Persistent<KeyBindings>>,
 ) {
-    let input_map = InputMap::new([
-        (PlayerAction::MainThrusterBig, KeyCode::Digit2),
-        (PlayerAction::MainThrusterBig, KeyCode::Space),
-        (PlayerAction::MainThrusterMedium, KeyCode::KeyW),
-        (PlayerAction::MainThrusterMedium, KeyCode::ArrowUp),
-        (PlayerAction::MainThrusterSmall, KeyCode::KeyS),
-        (PlayerAction::MainThrusterSmall, KeyCode::ArrowDown),
-        (PlayerAction::LeftThruster, KeyCode::KeyA),
-        (PlayerAction::LeftThruster, KeyCode::ArrowLeft),
-        (PlayerAction::RightThruster, KeyCode::KeyD),
-        (PlayerAction::RightThruster, KeyCode::ArrowRight),
-    ]);
+    let input_map = key_bindings.player_input_map();
     let sprite_image_handle = scene_assets.lander.clone();
     let sprite_image = image_assets.get(&sprite_image_handle);
-    let collider = single_convex_polyline_collider_translated(sprite_image.unwrap()).unwrap();
+    let collider = lander_collider(sprite_image.unwrap());
     commands.spawn((
         StateScoped(AppState::Game),
-        CharacterControllerBundle::new(collider).with_movement(
-            550.0, // before 1250.0
-            0.965, // before 0.92
-            4.9,   // before 60.0
-        ),
-        Friction::ZERO,
-        Restitution::new(0.0).with_combine_rule(CoefficientCombine::Min),
-        ColliderDensity(2.0),
-        GravityScale(scores.gravity),
+        lander_physics_bundle(collider, scores.gravity),
         SpriteBundle {
             texture: sprite_image_handle,
             transform: Transform::from_translation(INITIAL_SPACESHIP_POSITION),
@@ -61,12 +49,32 @@ fn spawn_spaceship_system(
             action_state: ActionState::default(),
             input_map,
         },
-        Player,
     ));
 }
 
+pub fn lander_collider(sprite_image: &Image) -> Collider {
+    single_convex_polyline_collider_translated(sprite_image).unwrap()
+}
+
+/// Physics components of the player lander, without any sprite or input handling.
+pub fn lander_physics_bundle(collider: Collider, gravity: f32) -> impl Bundle {
+    (
+        CharacterControllerBundle::new(collider).with_movement(
+            550.0,                   // before 1250.0
+            LANDER_MOVEMENT_DAMPING, // before 0.92
+            LANDER_JUMP_IMPULSE,     // before 60.0
+        ),
+        Friction::ZERO,
+        Restitution::new(0.0).with_combine_rule(CoefficientCombine::Min),
+        ColliderDensity(2.0),
+        GravityScale(gravity),
+        RadarAltimeter::default(),
+        Player,
+    )
+}
+
 // Actions
-#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
+#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect, Serialize, Deserialize)]
 pub enum PlayerAction {
     Idle,
     LeftThruster,
@@ -1,11 +1,23 @@
-use crate::game::InGameSet;
+use crate::accessibility::AccessibilitySettings;
+use crate::asset_loader::UiAssets;
+use crate::landing_rules::{speed_bar_offset, LandingRules, SPEED_BAR_HALF_HEIGHT};
 use crate::menu::BLACK_COLOR;
+use crate::radar::RadarAltimeter;
+use crate::settings::Settings;
 use crate::spaceship::Player;
 use crate::state::{AppState, GameState};
+use crate::WINDOW_WIDTH;
 use avian2d::dynamics::rigid_body::LinearVelocity;
 use bevy::prelude::*;
 use bevy::render::view::RenderLayers;
 use bevy::sprite::*;
+use bevy_persistent::Persistent;
+
+/// Speed bar pinned to the right edge of the right black bar, next to the instrument panel.
+const SPEED_BAR_X: f32 = WINDOW_WIDTH / 2.0 - 11.0;
+const SPEED_BAR_WIDTH: f32 = 15.0;
+/// Warning flashes per second while out of the landing window close to the ground.
+const WARNING_FLASH_FREQUENCY: f32 = 4.0;
 
 pub struct SpeedometerPlugin;
 
@@ -13,10 +25,8 @@ impl Plugin for SpeedometerPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(AppState::Game), spawn_speed_bar_system)
             .add_systems(
-                FixedUpdate,
-                update_speed_bar_black_indicator_system
-                    .run_if(in_state(GameState::Landing))
-                    .in_set(InGameSet::SpeedBar),
+                Update,
+                update_speedometer_system.run_if(in_state(GameState::Landing)),
             );
     }
 }
@@ -24,66 +34,158 @@ impl Plugin for SpeedometerPlugin {
 // Systems
 fn spawn_speed_bar_system(
     mut commands: Commands,
+    assets: Res<UiAssets>,
+    landing_rules: Res<LandingRules>,
+    settings: Res<Persistent<Settings>>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    // green bar range
+    let palette = settings.accessibility.palette;
+    // speed bar range, colored by the accessibility palette
     commands.spawn((
         StateScoped(AppState::Game),
         SpriteBundle {
-            transform: Transform::from_translation(Vec3::new(485.0, 0.0, 3.0)),
+            transform: Transform::from_translation(Vec3::new(SPEED_BAR_X, 0.0, 3.0)),
             sprite: Sprite {
-                color: Color::srgb(0.32, 0.75, 0.03),
-                custom_size: Some(Vec2::new(15.0, 600.0)),
+                color: palette.speed_bar(),
+                custom_size: Some(Vec2::new(SPEED_BAR_WIDTH, SPEED_BAR_HALF_HEIGHT * 2.0)),
                 ..default()
             },
             ..default()
         },
+        SpeedBar,
         RenderLayers::layer(2),
     ));
-    // yellow range
+    // safe band, resized to the landing window of the platform below
+    let (center, height) = safe_band(&landing_rules, None);
     commands.spawn((
         StateScoped(AppState::Game),
         SpriteBundle {
-            transform: Transform::from_translation(Vec3::new(485.0, -17.5, 4.0)),
+            transform: Transform::from_translation(Vec3::new(SPEED_BAR_X, center, 4.0)),
             sprite: Sprite {
-                color: Color::srgb(0.77, 0.84, 0.11),
-                custom_size: Some(Vec2::new(15.0, 35.0)),
+                color: palette.safe_band(),
+                custom_size: Some(Vec2::new(SPEED_BAR_WIDTH, height)),
                 ..default()
             },
             ..default()
         },
+        SpeedBarSafeBand,
         RenderLayers::layer(2),
     ));
     // black indicator
     commands.spawn((
         StateScoped(AppState::Game),
         MaterialMesh2dBundle {
-            transform: Transform::from_translation(Vec3::new(485.0, 0.0, 6.0)),
-            mesh: Mesh2dHandle(meshes.add(Rectangle::new(15.0, 2.0))),
+            transform: Transform::from_translation(Vec3::new(SPEED_BAR_X, 0.0, 6.0))
+                .with_scale(indicator_scale(&settings.accessibility)),
+            mesh: Mesh2dHandle(meshes.add(Rectangle::new(SPEED_BAR_WIDTH, 2.0))),
             material: materials.add(BLACK_COLOR),
             ..default()
         },
         SpeedBarBlackIndicator,
         RenderLayers::layer(2),
     ));
+    // numeric readout above the m/s unit
+    commands.spawn((
+        StateScoped(AppState::Game),
+        SpeedReadoutText,
+        TextBundle::from_section(
+            "0",
+            TextStyle {
+                font: assets.font_vt323.clone(),
+                font_size: 20.0,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(16.0),
+            right: Val::Px(2.0),
+            ..default()
+        }),
+    ));
 }
 
-fn update_speed_bar_black_indicator_system(
-    mut query_speed_bar_black_indicators: Query<&mut Transform, With<SpeedBarBlackIndicator>>,
-    mut query_player_linear_velocities: Query<&LinearVelocity, With<Player>>,
+fn update_speedometer_system(
+    time: Res<Time>,
+    landing_rules: Res<LandingRules>,
+    settings: Res<Persistent<Settings>>,
+    player_query: Query<(&LinearVelocity, &RadarAltimeter), With<Player>>,
+    mut black_indicator_query: Query<
+        &mut Transform,
+        (With<SpeedBarBlackIndicator>, Without<SpeedBarSafeBand>),
+    >,
+    mut safe_band_query: Query<(&mut Transform, &mut Sprite), With<SpeedBarSafeBand>>,
+    mut speed_bar_query: Query<&mut Sprite, (With<SpeedBar>, Without<SpeedBarSafeBand>)>,
+    mut readout_query: Query<&mut Text, With<SpeedReadoutText>>,
 ) {
-    let Ok(mut black_indicator) = query_speed_bar_black_indicators.get_single_mut() else {
-        return;
-    };
-    let Ok(linear_velocity) = query_player_linear_velocities.get_single_mut() else {
+    let Ok((linear_velocity, radar_altimeter)) = player_query.get_single() else {
         return;
     };
-    if linear_velocity.y < 300.0 && linear_velocity.y > -300.0 {
-        black_indicator.translation.y = linear_velocity.y;
+    let platform_factor = radar_altimeter.platform_below();
+    let accessibility = &settings.accessibility;
+    let palette = accessibility.palette;
+    if let Ok(mut black_indicator) = black_indicator_query.get_single_mut() {
+        black_indicator.translation.y = speed_bar_offset(linear_velocity.y);
+        black_indicator.scale = indicator_scale(accessibility);
+    }
+    if let Ok(mut sprite) = speed_bar_query.get_single_mut() {
+        sprite.color = palette.speed_bar();
+    }
+    let warning =
+        landing_rules.is_warning(linear_velocity.y, platform_factor, radar_altimeter.height());
+    // without blinking the warning color stays steady
+    let flash_on = warning
+        && (!accessibility.blinking
+            || (time.elapsed_seconds() * WARNING_FLASH_FREQUENCY).fract() < 0.5);
+    if let Ok((mut transform, mut sprite)) = safe_band_query.get_single_mut() {
+        let (center, height) = safe_band(&landing_rules, platform_factor);
+        transform.translation.y = center;
+        sprite.custom_size = Some(Vec2::new(SPEED_BAR_WIDTH, height));
+        sprite.color = if flash_on {
+            palette.warning()
+        } else {
+            palette.safe_band()
+        };
+    }
+    if let Ok(mut text) = readout_query.get_single_mut() {
+        let section = &mut text.sections[0];
+        section.value = format!("{:.0}", linear_velocity.y);
+        section.style.color = if flash_on {
+            palette.warning()
+        } else if landing_rules.is_safe(linear_velocity.y, platform_factor) {
+            palette.safe_band()
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+/// Center and height of the safe band drawn over the speed bar.
+fn safe_band(landing_rules: &LandingRules, platform_factor: Option<i32>) -> (f32, f32) {
+    let window = landing_rules.window(platform_factor);
+    let (bottom, top) = (speed_bar_offset(window.min), speed_bar_offset(window.max));
+    ((bottom + top) / 2.0, top - bottom)
+}
+
+/// The high contrast indicator is twice as thick.
+fn indicator_scale(accessibility: &AccessibilitySettings) -> Vec3 {
+    if accessibility.high_contrast {
+        Vec3::new(1.0, 2.0, 1.0)
+    } else {
+        Vec3::ONE
     }
 }
 
 // Components
+#[derive(Component, Debug)]
+struct SpeedBar;
+
 #[derive(Component, Debug)]
 pub struct SpeedBarBlackIndicator;
+
+#[derive(Component, Debug)]
+struct SpeedBarSafeBand;
+
+#[derive(Component, Debug)]
+struct SpeedReadoutText;
@@ -1,10 +1,17 @@
 use crate::asset_loader::MusicAssets;
 use crate::audio::MusicPlayingSoundEffect;
 use crate::game::Resettable;
+use crate::settings::Settings;
 use crate::spaceship::{Player, INITIAL_SPACESHIP_POSITION};
 use avian2d::prelude::{LinearVelocity, Physics, PhysicsTime};
 use bevy::audio::PlaybackMode;
 use bevy::prelude::*;
+use bevy_persistent::Persistent;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+const INITIAL_SPACESHIP_VELOCITY: Vec2 = Vec2::new(120.0, 0.0);
 
 // States
 #[derive(States, Debug, Copy, Clone, Hash, Eq, PartialEq, Default)]
@@ -15,10 +22,15 @@ pub enum AppState {
     Menu,
     Game,
     Instructions,
+    Controls,
+    Settings,
+    HallOfFame,
     Credits,
+    Profiles,
+    Statistics,
 }
 
-#[derive(SubStates, Debug, Copy, Clone, Hash, Eq, PartialEq, Default)]
+#[derive(SubStates, Debug, Copy, Clone, Hash, Eq, PartialEq, Default, Serialize, Deserialize)]
 #[source(AppState=AppState::Game)]
 pub enum GameState {
     #[default]
@@ -35,18 +47,52 @@ pub enum GameState {
 #[derive(Resource)]
 pub struct TenSecondsTimer(pub Timer);
 
+/// Spaceship position and velocity at the beginning of every landing attempt.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct LaunchConditions {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    /// Seed the conditions were derived from, None for the default ones
+    pub seed: Option<u64>,
+}
+
+impl Default for LaunchConditions {
+    fn default() -> Self {
+        Self {
+            position: INITIAL_SPACESHIP_POSITION.xy(),
+            velocity: INITIAL_SPACESHIP_VELOCITY,
+            seed: None,
+        }
+    }
+}
+
+impl LaunchConditions {
+    /// Reproducible launch conditions around the default ones.
+    pub fn from_seed(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let default = Self::default();
+        Self {
+            position: default.position
+                + Vec2::new(rng.gen_range(-150.0..150.0), rng.gen_range(-50.0..50.0)),
+            velocity: Vec2::new(rng.gen_range(-120.0..120.0), rng.gen_range(-20.0..10.0)),
+            seed: Some(seed),
+        }
+    }
+}
+
 pub struct StatesPlugin;
 
 impl Plugin for StatesPlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<AppState>()
+        app.init_resource::<LaunchConditions>()
+            .init_state::<AppState>()
             .enable_state_scoped_entities::<AppState>()
             .add_sub_state::<GameState>()
             .enable_state_scoped_entities::<GameState>()
             .add_systems(
                 Update,
                 (
-                    transition_app_setup_to_menu_system.run_if(in_state(AppState::Setup)),
+                    transition_app_setup_to_profiles_system.run_if(in_state(AppState::Setup)),
                     transition_game_setup_to_running_system.run_if(in_state(GameState::Setup)),
                     check_ten_seconds_timer.run_if(in_state(GameState::Landing)),
                 ),
@@ -55,8 +101,8 @@ impl Plugin for StatesPlugin {
 }
 
 // Systems
-fn transition_app_setup_to_menu_system(mut state: ResMut<NextState<AppState>>) {
-    state.set(AppState::Menu);
+fn transition_app_setup_to_profiles_system(mut state: ResMut<NextState<AppState>>) {
+    state.set(AppState::Profiles);
 }
 
 fn transition_game_setup_to_running_system(
@@ -67,6 +113,7 @@ fn transition_game_setup_to_running_system(
     mut spaceship_linear_velocity_query: Query<&mut LinearVelocity, With<Player>>,
     mut spaceship_visibility_query: Query<&mut Visibility, With<Player>>,
     mut physics_time: ResMut<Time<Physics>>,
+    launch_conditions: Res<LaunchConditions>,
 ) {
     physics_time.unpause();
     for entity in resettable_text_query.iter() {
@@ -78,10 +125,9 @@ fn transition_game_setup_to_running_system(
     let Ok(mut spaceship_linear_velocity) = spaceship_linear_velocity_query.get_single_mut() else {
         return;
     };
-    spaceship_transform.translation.x = INITIAL_SPACESHIP_POSITION.x;
-    spaceship_transform.translation.y = INITIAL_SPACESHIP_POSITION.y;
-    spaceship_linear_velocity.x = 120.0;
-    spaceship_linear_velocity.y = 0.0;
+    spaceship_transform.translation.x = launch_conditions.position.x;
+    spaceship_transform.translation.y = launch_conditions.position.y;
+    spaceship_linear_velocity.0 = launch_conditions.velocity;
     let mut spaceship_visibility = spaceship_visibility_query.single_mut();
     *spaceship_visibility = Visibility::Visible;
     state.set(GameState::Landing);
@@ -90,6 +136,7 @@ fn transition_game_setup_to_running_system(
 fn check_ten_seconds_timer(
     time: Res<Time>,
     music_assets: Res<MusicAssets>,
+    settings: Res<Persistent<Settings>>,
     mut timer: ResMut<TenSecondsTimer>,
     mut commands: Commands,
 ) {
@@ -101,6 +148,7 @@ fn check_ten_seconds_timer(
                 source: music_assets.music_playing.clone(),
                 settings: PlaybackSettings {
                     mode: PlaybackMode::Once,
+                    volume: settings.music_volume(),
                     ..default()
                 },
             },
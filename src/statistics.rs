@@ -0,0 +1,441 @@
+use bevy::prelude::*;
+use bevy_persistent::Persistent;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::asset_loader::UiAssets;
+use crate::attract::AttractMode;
+use crate::explosion::{CrashCause, SpawnExplosionEvent};
+use crate::game::{OutOfFuelEvent, Scores, SpaceshipJustLandedEvent};
+use crate::localization::Localization;
+use crate::menu::{screen_entries, MenuEntryAction, MenuHandler, GREY_COLOR, PRIMARY_COLOR};
+use crate::profile::{ProfileSelectedEvent, Profiles};
+use crate::session::{end_session_on_exit_system, SessionEndedEvent};
+use crate::state::{AppState, GameState};
+use crate::storage::{load_persistent, Versioned};
+
+const CRASH_CAUSES: [CrashCause; 4] = [
+    CrashCause::TooFast,
+    CrashCause::OffPlatform,
+    CrashCause::Terrain,
+    CrashCause::WorldBounds,
+];
+
+pub struct StatisticsPlugin;
+
+impl Plugin for StatisticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, persist_statistics_system)
+            .add_systems(
+                PostUpdate,
+                persist_statistics_system.run_if(on_event::<ProfileSelectedEvent>()),
+            )
+            .add_systems(
+                OnEnter(GameState::Setup),
+                start_flight_system.run_if(recording_statistics),
+            )
+            .add_systems(
+                OnEnter(AppState::Statistics),
+                spawn_statistics_screen_system,
+            )
+            .add_systems(
+                Update,
+                (
+                    track_fuel_burned_system.run_if(in_state(GameState::Landing)),
+                    record_landing_system.run_if(on_event::<SpaceshipJustLandedEvent>()),
+                    record_crash_system.run_if(on_event::<SpawnExplosionEvent>()),
+                    record_out_of_fuel_system.run_if(on_event::<OutOfFuelEvent>()),
+                )
+                    .run_if(recording_statistics),
+            )
+            .add_systems(
+                Last,
+                record_session_system
+                    .run_if(on_event::<SessionEndedEvent>())
+                    .after(end_session_on_exit_system),
+            );
+    }
+}
+
+// Conditions
+/// The attract mode demo flights are left out of the statistics.
+fn recording_statistics(attract_mode: Res<AttractMode>) -> bool {
+    !attract_mode.active
+}
+
+// Systems
+fn persist_statistics_system(mut commands: Commands, profiles: Res<Persistent<Profiles>>) {
+    commands.insert_resource(load_persistent::<Statistics>(
+        "statistics",
+        &profiles.active_dir(),
+        "statistics.json",
+    ));
+}
+
+fn start_flight_system(
+    mut commands: Commands,
+    scores: Res<Scores>,
+    flight_log: Option<Res<FlightLog>>,
+    mut statistics: ResMut<Persistent<Statistics>>,
+) {
+    commands.insert_resource(FlightLog {
+        fuel_quantity: scores.fuel_quantity,
+        fuel_burned: flight_log.map_or(0.0, |flight_log| flight_log.fuel_burned),
+    });
+    let gravity = scores.gravity;
+    if let Err(error) = statistics.update(|statistics| {
+        statistics.flights += 1;
+        statistics.highest_gravity = statistics.highest_gravity.max(gravity);
+    }) {
+        error!("failed to update the statistics: {}", error);
+    }
+}
+
+/// Adds up the fuel spent by the thrusters, the fuel given back by landings aside.
+fn track_fuel_burned_system(scores: Res<Scores>, flight_log: Option<ResMut<FlightLog>>) {
+    let Some(mut flight_log) = flight_log else {
+        return;
+    };
+    let fuel_quantity = scores.fuel_quantity.max(0.0);
+    if fuel_quantity < flight_log.fuel_quantity {
+        flight_log.fuel_burned += flight_log.fuel_quantity - fuel_quantity;
+    }
+    flight_log.fuel_quantity = fuel_quantity;
+}
+
+fn record_landing_system(
+    flight_log: Option<ResMut<FlightLog>>,
+    mut events_reader: EventReader<SpaceshipJustLandedEvent>,
+    mut statistics: ResMut<Persistent<Statistics>>,
+) {
+    let fuel_burned = take_fuel_burned(flight_log);
+    for event in events_reader.read().filter(|event| !event.autopilot) {
+        let factor = event.platform.factor;
+        let vertical_velocity = event.linear_velocity.y;
+        let score = event.score;
+        if let Err(error) = statistics.update(|statistics| {
+            statistics.record_landing(factor, vertical_velocity, score);
+            statistics.fuel_burned += fuel_burned;
+        }) {
+            error!("failed to update the statistics: {}", error);
+        }
+    }
+}
+
+fn record_crash_system(
+    flight_log: Option<ResMut<FlightLog>>,
+    mut events_reader: EventReader<SpawnExplosionEvent>,
+    mut statistics: ResMut<Persistent<Statistics>>,
+) {
+    let fuel_burned = take_fuel_burned(flight_log);
+    for event in events_reader.read() {
+        let cause = event.cause;
+        if let Err(error) = statistics.update(|statistics| {
+            *statistics.crashes.entry(cause).or_default() += 1;
+            statistics.fuel_burned += fuel_burned;
+        }) {
+            error!("failed to update the statistics: {}", error);
+        }
+    }
+}
+
+fn record_out_of_fuel_system(
+    mut events_reader: EventReader<OutOfFuelEvent>,
+    mut statistics: ResMut<Persistent<Statistics>>,
+) {
+    events_reader.clear();
+    if let Err(error) = statistics.update(|statistics| statistics.out_of_fuel += 1) {
+        error!("failed to update the statistics: {}", error);
+    }
+}
+
+fn record_session_system(
+    mut commands: Commands,
+    flight_log: Option<ResMut<FlightLog>>,
+    mut events_reader: EventReader<SessionEndedEvent>,
+    mut statistics: ResMut<Persistent<Statistics>>,
+) {
+    let fuel_burned = take_fuel_burned(flight_log);
+    commands.remove_resource::<FlightLog>();
+    for event in events_reader.read().filter(|event| !event.demo) {
+        let flight_time = event.flight_time;
+        if let Err(error) = statistics.update(|statistics| {
+            statistics.flight_time += flight_time;
+            statistics.fuel_burned += fuel_burned;
+        }) {
+            error!("failed to update the statistics: {}", error);
+        }
+    }
+}
+
+/// Fuel burned since the last time it was recorded.
+fn take_fuel_burned(flight_log: Option<ResMut<FlightLog>>) -> f32 {
+    flight_log.map_or(0.0, |mut flight_log| {
+        std::mem::take(&mut flight_log.fuel_burned)
+    })
+}
+
+fn spawn_statistics_screen_system(
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    localization: Res<Localization>,
+    statistics: Res<Persistent<Statistics>>,
+) {
+    let entity = MenuHandler {
+        main_text: "".into(),
+        main_text_color: PRIMARY_COLOR,
+        main_text_blink: false,
+        selected_id: 0,
+        entries: screen_entries(&localization),
+        escape_action: Some(MenuEntryAction::Back),
+        ..default()
+    }
+    .spawn(&mut commands, assets.font_kenvector.clone());
+    commands
+        .entity(entity)
+        .insert(StateScoped(AppState::Statistics));
+    let text_style = |color: Color| TextStyle {
+        font: assets.font_vt323.clone(),
+        font_size: 26.0,
+        color,
+    };
+    commands
+        .spawn((
+            StateScoped(AppState::Statistics),
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(70.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    localization.text("menu.statistics"),
+                    TextStyle {
+                        font: assets.font_kenvector.clone(),
+                        font_size: 50.0,
+                        color: PRIMARY_COLOR,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                }),
+            );
+            for (label, value, detail) in statistics_rows(&statistics, &localization) {
+                parent.spawn(TextBundle::from_section(
+                    statistics_row(&label, &value),
+                    text_style(if detail { GREY_COLOR } else { Color::WHITE }),
+                ));
+            }
+        });
+}
+
+/// Label, value and whether it details the previous total.
+fn statistics_rows(
+    statistics: &Statistics,
+    localization: &Localization,
+) -> Vec<(String, String, bool)> {
+    let text = |key: &str| localization.text(key).to_string();
+    let mut rows = vec![
+        (
+            text("statistics.flights"),
+            statistics.flights.to_string(),
+            false,
+        ),
+        (
+            text("statistics.landings"),
+            statistics.total_landings().to_string(),
+            false,
+        ),
+    ];
+    for (factor, landings) in statistics.landings.iter() {
+        rows.push((
+            localization.format("statistics.platform", &[factor.to_string().as_str()]),
+            landings.to_string(),
+            true,
+        ));
+    }
+    rows.push((
+        text("statistics.crashes"),
+        statistics.total_crashes().to_string(),
+        false,
+    ));
+    for cause in CRASH_CAUSES {
+        rows.push((
+            text(crash_cause_key(cause)),
+            statistics.crashes(cause).to_string(),
+            true,
+        ));
+    }
+    rows.extend([
+        (
+            text("statistics.out_of_fuel"),
+            statistics.out_of_fuel.to_string(),
+            false,
+        ),
+        (
+            text("statistics.fuel_burned"),
+            format!("{:.0}", statistics.fuel_burned),
+            false,
+        ),
+        (
+            text("statistics.flight_time"),
+            format_duration(statistics.flight_time),
+            false,
+        ),
+        (
+            text("statistics.best_landing"),
+            statistics.best_landing.to_string(),
+            false,
+        ),
+        (
+            text("statistics.highest_gravity"),
+            format!("x{:.1}", statistics.highest_gravity),
+            false,
+        ),
+        (
+            text("statistics.touchdown_speed"),
+            statistics
+                .average_touchdown_speed()
+                .map_or("-".into(), |speed| {
+                    format!("{:.0} {}", speed, localization.text("hud.speed_unit"))
+                }),
+            false,
+        ),
+    ]);
+    rows
+}
+
+/// Table row padded for the monospaced VT323 font.
+fn statistics_row(label: &str, value: &str) -> String {
+    format!("{:<28}{:>12}", label, value)
+}
+
+fn crash_cause_key(cause: CrashCause) -> &'static str {
+    match cause {
+        CrashCause::TooFast => "statistics.too_fast",
+        CrashCause::OffPlatform => "statistics.off_platform",
+        CrashCause::Terrain => "statistics.terrain",
+        CrashCause::WorldBounds => "statistics.world_bounds",
+    }
+}
+
+/// `H:MM:SS` of a duration.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+// Resources
+/// Lifetime statistics of the active profile.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Statistics {
+    #[serde(default)]
+    pub version: u32,
+    /// Landing attempts, every launch of the lander
+    pub flights: u32,
+    /// Landings per platform factor
+    pub landings: BTreeMap<i32, u32>,
+    pub crashes: BTreeMap<CrashCause, u32>,
+    /// Flights that ran out of fuel
+    pub out_of_fuel: u32,
+    pub fuel_burned: f32,
+    pub flight_time: Duration,
+    /// Best score of a single landing
+    pub best_landing: i32,
+    pub highest_gravity: f32,
+    /// Sum of the touchdown vertical speeds, for their average
+    pub touchdown_speed_total: f32,
+}
+
+impl Default for Statistics {
+    fn default() -> Self {
+        Self {
+            version: Self::VERSION,
+            flights: 0,
+            landings: BTreeMap::new(),
+            crashes: BTreeMap::new(),
+            out_of_fuel: 0,
+            fuel_burned: 0.0,
+            flight_time: Duration::ZERO,
+            best_landing: 0,
+            highest_gravity: 0.0,
+            touchdown_speed_total: 0.0,
+        }
+    }
+}
+
+impl Versioned for Statistics {
+    const VERSION: u32 = 1;
+}
+
+impl Statistics {
+    pub fn record_landing(&mut self, factor: i32, vertical_velocity: f32, score: i32) {
+        *self.landings.entry(factor).or_default() += 1;
+        self.best_landing = self.best_landing.max(score);
+        self.touchdown_speed_total += vertical_velocity.abs();
+    }
+
+    pub fn total_landings(&self) -> u32 {
+        self.landings.values().sum()
+    }
+
+    pub fn crashes(&self, cause: CrashCause) -> u32 {
+        self.crashes.get(&cause).copied().unwrap_or_default()
+    }
+
+    pub fn total_crashes(&self) -> u32 {
+        self.crashes.values().sum()
+    }
+
+    /// None until the first landing.
+    pub fn average_touchdown_speed(&self) -> Option<f32> {
+        match self.total_landings() {
+            0 => None,
+            landings => Some(self.touchdown_speed_total / landings as f32),
+        }
+    }
+}
+
+/// Fuel burned during the flights not yet recorded, and the fuel left when last checked.
+#[derive(Resource, Debug)]
+struct FlightLog {
+    fuel_quantity: f32,
+    fuel_burned: f32,
+}
+
+#[test]
+fn statistics_test() {
+    let mut statistics = Statistics::default();
+    assert_eq!(statistics.average_touchdown_speed(), None);
+    statistics.record_landing(2, -10.0, 100);
+    statistics.record_landing(5, -20.0, 250);
+    statistics.record_landing(2, -30.0, 50);
+    *statistics.crashes.entry(CrashCause::Terrain).or_default() += 1;
+    assert_eq!(statistics.total_landings(), 3);
+    assert_eq!(statistics.landings[&2], 2);
+    assert_eq!(statistics.best_landing, 250);
+    assert_eq!(statistics.average_touchdown_speed(), Some(20.0));
+    assert_eq!(statistics.crashes(CrashCause::Terrain), 1);
+    assert_eq!(statistics.crashes(CrashCause::TooFast), 0);
+    let json = serde_json::to_string(&statistics).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Statistics>(&json).unwrap(),
+        statistics
+    );
+    assert_eq!(format_duration(Duration::from_secs(3725)), "1:02:05");
+}
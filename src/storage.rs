@@ -0,0 +1,271 @@
+//! Loading of the persisted files: every file carries a schema version and is migrated from older
+//! versions. The last copy loaded fine is kept as a backup, restored when the file can't be read,
+//! and the defaults are used when neither can be read. When no folder can be written the defaults
+//! are kept in memory only.
+use bevy::prelude::*;
+use bevy_persistent::{PersistenceError, Persistent, StorageFormat};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// File beside the executable enabling the portable mode.
+const PORTABLE_MARKER: &str = "portable.txt";
+#[cfg(windows)]
+const NULL_DEVICE: &str = "NUL";
+#[cfg(not(windows))]
+const NULL_DEVICE: &str = "/dev/null";
+
+/// Persisted resource with a schema version, serialized as its `version` field.
+pub trait Versioned: Resource + Serialize + DeserializeOwned + Default {
+    /// Current schema version, files without a version are version 0
+    const VERSION: u32;
+
+    /// Upgrades `value` from `version` to `version + 1`.
+    fn migrate(value: Value, version: u32) -> Value {
+        let _ = version;
+        value
+    }
+}
+
+/// Folder of the persisted files, beside the executable in portable mode (when a `portable.txt`
+/// file is found there), in the user config folder otherwise.
+pub fn data_dir() -> &'static Path {
+    static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+    DATA_DIR.get_or_init(|| {
+        let executable_dir = std::env::current_exe()
+            .ok()
+            .and_then(|path| path.parent().map(Path::to_path_buf));
+        if let Some(executable_dir) = executable_dir
+            .as_ref()
+            .filter(|dir| dir.join(PORTABLE_MARKER).exists())
+        {
+            let data_dir = executable_dir.join("data");
+            info!("portable mode, data stored in {}", data_dir.display());
+            return data_dir;
+        }
+        match dirs::config_dir() {
+            Some(config_dir) => config_dir.join("RustyLander"),
+            None => {
+                let data_dir = executable_dir.unwrap_or_default().join("data");
+                warn!(
+                    "no user config folder, data stored in {}",
+                    data_dir.display()
+                );
+                data_dir
+            }
+        }
+    })
+}
+
+/// Loads `file_name` from `dir`, migrated to the current version, or defaults when missing or
+/// unreadable.
+pub fn load_persistent<T: Versioned>(name: &str, dir: &Path, file_name: &str) -> Persistent<T> {
+    // when the data folder can't be written nothing will be remembered after this run
+    let fallback_dir = std::env::temp_dir().join("RustyLander");
+    load_first_writable::<T>(name, &[dir.join(file_name), fallback_dir.join(file_name)])
+}
+
+/// Loads the first of `paths` that can be written, or keeps the defaults in memory only when none
+/// of them can.
+fn load_first_writable<T: Versioned>(name: &str, paths: &[PathBuf]) -> Persistent<T> {
+    for path in paths {
+        prepare_file::<T>(name, path);
+        match build_persistent::<T>(name, path, false) {
+            Ok(persistent) => return persistent,
+            Err(error) => error!("failed to load {} from {}: {}", name, path.display(), error),
+        }
+    }
+    error!(
+        "{} can't be saved anywhere, changes will be lost on exit",
+        name
+    );
+    // the null device reads as corrupt, so the defaults are used, and discards every write
+    build_persistent::<T>(name, Path::new(NULL_DEVICE), true)
+        .expect("the null device accepts every write")
+}
+
+fn build_persistent<T: Versioned>(
+    name: &str,
+    path: &Path,
+    revert_to_default: bool,
+) -> Result<Persistent<T>, PersistenceError> {
+    Persistent::<T>::builder()
+        .name(name)
+        .format(StorageFormat::Json)
+        .path(path)
+        .default(T::default())
+        .revert_to_default_on_deserialization_errors(revert_to_default)
+        .build()
+}
+
+/// Migrates the file at `path` when it's outdated and backs it up, or moves it aside when it's
+/// corrupt and restores the backup.
+fn prepare_file<T: Versioned>(name: &str, path: &Path) {
+    let Ok(content) = fs::read_to_string(path) else {
+        return;
+    };
+    match upgrade::<T>(&content) {
+        Ok(upgraded) => {
+            if let Some(upgraded) = upgraded {
+                let migration_backup_path = backup_path(path, &format!("pre-v{}.bak", T::VERSION));
+                let result = fs::copy(path, &migration_backup_path)
+                    .and_then(|_| fs::write(path, upgraded.as_bytes()));
+                match result {
+                    Ok(()) => info!(
+                        "{} migrated to version {}, previous file kept as {}",
+                        name,
+                        T::VERSION,
+                        migration_backup_path.display()
+                    ),
+                    Err(error) => error!("failed to migrate {}: {}", name, error),
+                }
+            }
+            if let Err(error) = fs::copy(path, backup_path(path, "bak")) {
+                warn!("failed to back up {}: {}", name, error);
+            }
+        }
+        Err(reason) => {
+            let corrupt_path = backup_path(path, "corrupt");
+            if let Err(error) = fs::rename(path, &corrupt_path) {
+                error!("failed to move corrupt {} aside: {}", name, error);
+                let _ = fs::remove_file(path);
+            }
+            let recovery = match restore_backup::<T>(path) {
+                Ok(()) => "restored from its backup".to_string(),
+                Err(error) => format!("recovered with defaults, no usable backup ({})", error),
+            };
+            warn!(
+                "{} could not be read ({}), {}, the corrupt file was kept as {}",
+                name,
+                reason,
+                recovery,
+                corrupt_path.display()
+            );
+        }
+    }
+}
+
+/// Puts the last copy of the file at `path` that loaded fine back in place, at the current version.
+fn restore_backup<T: Versioned>(path: &Path) -> Result<(), String> {
+    let content =
+        fs::read_to_string(backup_path(path, "bak")).map_err(|error| error.to_string())?;
+    let restored = upgrade::<T>(&content)?.unwrap_or(content);
+    fs::write(path, restored).map_err(|error| error.to_string())
+}
+
+/// Serialized `content` upgraded to the current version, None when it's already current.
+fn upgrade<T: Versioned>(content: &str) -> Result<Option<String>, String> {
+    let mut value: Value = serde_json::from_str(content).map_err(|error| error.to_string())?;
+    if !value.is_object() {
+        return Err("not a JSON object".into());
+    }
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .unwrap_or_default() as u32;
+    for from_version in version..T::VERSION {
+        value = T::migrate(value, from_version);
+    }
+    if version < T::VERSION {
+        value["version"] = T::VERSION.into();
+    }
+    // files from newer versions are kept as long as they can be read
+    let data: T = serde_json::from_value(value).map_err(|error| error.to_string())?;
+    if version >= T::VERSION {
+        return Ok(None);
+    }
+    serde_json::to_string(&data)
+        .map(Some)
+        .map_err(|error| error.to_string())
+}
+
+pub(crate) fn backup_path(path: &Path, extension: &str) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(extension);
+    path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+#[derive(Resource, Serialize, serde::Deserialize, Debug, Default, PartialEq)]
+struct TestData {
+    version: u32,
+    value: i32,
+}
+
+#[cfg(test)]
+impl Versioned for TestData {
+    const VERSION: u32 = 2;
+
+    fn migrate(mut value: Value, version: u32) -> Value {
+        // version 1 doubled the value, version 2 renamed it
+        match version {
+            0 => value["old_value"] = (value["old_value"].as_i64().unwrap() * 2).into(),
+            _ => value["value"] = value["old_value"].take(),
+        }
+        value
+    }
+}
+
+#[test]
+fn storage_test() {
+    let upgraded = upgrade::<TestData>(r#"{"old_value":21}"#).unwrap().unwrap();
+    let data: TestData = serde_json::from_str(&upgraded).unwrap();
+    assert_eq!(
+        data,
+        TestData {
+            version: 2,
+            value: 42
+        }
+    );
+    assert_eq!(upgrade::<TestData>(&upgraded), Ok(None));
+    assert!(upgrade::<TestData>(r#"{"version":2,"value":"#).is_err());
+    assert!(upgrade::<TestData>("[]").is_err());
+    assert_eq!(
+        backup_path(Path::new("/tmp/settings.json"), "bak"),
+        Path::new("/tmp/settings.json.bak")
+    );
+}
+
+#[test]
+fn storage_backup_test() {
+    let dir = std::env::temp_dir().join(format!("rusty-lander-storage-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("data.json");
+    fs::write(&path, r#"{"version":2,"value":7}"#).unwrap();
+    prepare_file::<TestData>("data", &path);
+    assert!(backup_path(&path, "bak").exists());
+    fs::write(&path, r#"{"version":2,"val"#).unwrap();
+    prepare_file::<TestData>("data", &path);
+    let restored: TestData = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(restored.value, 7);
+    assert!(backup_path(&path, "corrupt").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn storage_unwritable_dir_test() {
+    let dir = std::env::temp_dir().join(format!("rusty-lander-unwritable-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    // a file where the data folder should be, so nothing can be written below it
+    let blocked_dir = dir.join("blocked");
+    fs::write(&blocked_dir, "").unwrap();
+    let blocked_path = blocked_dir.join("data.json");
+    // the fallback file is recovered like any other
+    let fallback_path = dir.join("data.json");
+    fs::write(
+        backup_path(&fallback_path, "bak"),
+        r#"{"version":2,"value":7}"#,
+    )
+    .unwrap();
+    fs::write(&fallback_path, r#"{"version":2,"val"#).unwrap();
+    let data = load_first_writable::<TestData>("data", &[blocked_path.clone(), fallback_path]);
+    assert_eq!(data.value, 7);
+    // nowhere to write, defaults kept in memory
+    let data = load_first_writable::<TestData>("data", &[blocked_path]);
+    assert_eq!(*data, TestData::default());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,224 @@
+use avian2d::prelude::{Gravity, GravityScale, LinearVelocity};
+use bevy::color::palettes::css::{ORANGE_RED, SPRING_GREEN};
+use bevy::prelude::*;
+
+use crate::asset_loader::UiAssets;
+use crate::assists::trajectory_assist_enabled;
+use crate::autopilot::{LandingPad, LANDER_HALF_HEIGHT};
+use crate::collider::{
+    landscape_world_vertices, terrain_height_at, Platform, WORLD_BOUNDS_MAX_X, WORLD_BOUNDS_MIN_X,
+    WORLD_BOUNDS_MIN_Y,
+};
+use crate::game::WorldBoundsVertices2D;
+use crate::landing_rules::LandingRules;
+use crate::spaceship::{Player, LANDER_MOVEMENT_DAMPING};
+use crate::state::{AppState, GameState};
+
+const PREDICTION_TICKS_PER_SECOND: f32 = 64.0;
+const PREDICTION_MAX_SECONDS: f32 = 12.0;
+const PLATFORM_HALF_HEIGHT: f32 = 4.0;
+
+pub struct TrajectoryPlugin;
+
+impl Plugin for TrajectoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Game), spawn_impact_text_system)
+            .add_systems(
+                Update,
+                (
+                    draw_predicted_trajectory_system
+                        .run_if(in_state(GameState::Landing))
+                        .run_if(trajectory_assist_enabled),
+                    hide_impact_text_system.run_if(
+                        not(in_state(GameState::Landing)).or_else(not(trajectory_assist_enabled)),
+                    ),
+                )
+                    .run_if(in_state(AppState::Game)),
+            );
+    }
+}
+
+// Systems
+fn spawn_impact_text_system(mut commands: Commands, assets: Res<UiAssets>) {
+    commands.spawn((
+        StateScoped(AppState::Game),
+        ImpactText,
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: assets.font_vt323.clone(),
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                },
+            ),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+    ));
+}
+
+fn draw_predicted_trajectory_system(
+    mut gizmos: Gizmos,
+    gravity: Res<Gravity>,
+    landing_rules: Res<LandingRules>,
+    landscape: Res<WorldBoundsVertices2D>,
+    platforms_query: Query<(&Platform, &Transform)>,
+    spaceship_query: Query<(&Transform, &LinearVelocity, &GravityScale), With<Player>>,
+    mut impact_text_query: Query<
+        (&mut Text, &mut Transform, &mut Visibility),
+        (With<ImpactText>, Without<Player>, Without<Platform>),
+    >,
+    mut terrain: Local<Vec<Vec2>>,
+) {
+    let Ok((transform, linear_velocity, gravity_scale)) = spaceship_query.get_single() else {
+        return;
+    };
+    if terrain.is_empty() {
+        *terrain = landscape_world_vertices(&landscape);
+    }
+    let pads: Vec<LandingPad> = platforms_query
+        .iter()
+        .map(|(platform, transform)| LandingPad::new(platform, transform))
+        .collect();
+    let trajectory = predict_trajectory(
+        transform.translation.xy(),
+        linear_velocity.0,
+        gravity.0.y.abs() * gravity_scale.0,
+        &terrain,
+        &pads,
+    );
+    gizmos.linestrip_2d(
+        trajectory.points.iter().copied(),
+        Color::srgba(1.0, 1.0, 1.0, 0.5),
+    );
+    let Ok((mut text, mut text_transform, mut visibility)) = impact_text_query.get_single_mut()
+    else {
+        return;
+    };
+    let Some(impact) = trajectory.impact else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    let color = if impact.is_safe(&landing_rules) {
+        Color::from(SPRING_GREEN)
+    } else {
+        Color::from(ORANGE_RED)
+    };
+    gizmos.circle_2d(impact.point, 8.0, color);
+    gizmos.line_2d(
+        impact.point - Vec2::X * 14.0,
+        impact.point + Vec2::X * 14.0,
+        color,
+    );
+    text.sections[0].value = match impact.platform_factor {
+        Some(factor) => format!("{:.0} m/s x{}", impact.vertical_velocity, factor),
+        None => format!("{:.0} m/s", impact.vertical_velocity),
+    };
+    text.sections[0].style.color = color;
+    text_transform.translation = (impact.point + Vec2::new(0.0, 24.0)).extend(12.0);
+    *visibility = Visibility::Visible;
+}
+
+fn hide_impact_text_system(mut impact_text_query: Query<&mut Visibility, With<ImpactText>>) {
+    for mut visibility in impact_text_query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+/// Ballistic path followed by the lander center with every thruster off, simulated with the fixed tick
+/// physics until the lander bottom touches a platform or the terrain.
+pub fn predict_trajectory(
+    position: Vec2,
+    velocity: Vec2,
+    gravity: f32,
+    terrain: &[Vec2],
+    pads: &[LandingPad],
+) -> Trajectory {
+    let delta_time = 1.0 / PREDICTION_TICKS_PER_SECOND;
+    let mut position = position;
+    let mut velocity = velocity;
+    let mut points = vec![position];
+    let max_ticks = (PREDICTION_MAX_SECONDS * PREDICTION_TICKS_PER_SECOND) as usize;
+    for _ in 0..max_ticks {
+        velocity.y -= gravity * delta_time;
+        velocity.x *= LANDER_MOVEMENT_DAMPING;
+        let previous_bottom = position.y - LANDER_HALF_HEIGHT;
+        position += velocity * delta_time;
+        points.push(position);
+        if position.x < WORLD_BOUNDS_MIN_X || position.x > WORLD_BOUNDS_MAX_X {
+            break;
+        }
+        let bottom = position.y - LANDER_HALF_HEIGHT;
+        let platform = pads.iter().find(|pad| {
+            let top = pad.center.y + PLATFORM_HALF_HEIGHT;
+            (position.x - pad.center.x).abs() <= pad.half_width
+                && previous_bottom >= top
+                && bottom <= top
+        });
+        let surface_y = match platform {
+            Some(pad) => Some(pad.center.y + PLATFORM_HALF_HEIGHT),
+            None => terrain_height_at(terrain, position.x)
+                .filter(|terrain_y| bottom <= *terrain_y)
+                .or((bottom <= WORLD_BOUNDS_MIN_Y).then_some(WORLD_BOUNDS_MIN_Y)),
+        };
+        if let Some(surface_y) = surface_y {
+            return Trajectory {
+                points,
+                impact: Some(Impact {
+                    point: Vec2::new(position.x, surface_y),
+                    vertical_velocity: velocity.y,
+                    platform_factor: platform.map(|pad| pad.factor),
+                }),
+            };
+        }
+    }
+    Trajectory {
+        points,
+        impact: None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trajectory {
+    pub points: Vec<Vec2>,
+    pub impact: Option<Impact>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Impact {
+    pub point: Vec2,
+    pub vertical_velocity: f32,
+    pub platform_factor: Option<i32>,
+}
+
+impl Impact {
+    /// Whether touching down there right now would be a landing instead of a crash.
+    pub fn is_safe(&self, landing_rules: &LandingRules) -> bool {
+        self.platform_factor.is_some()
+            && landing_rules.is_safe(self.vertical_velocity, self.platform_factor)
+    }
+}
+
+// Components
+#[derive(Component)]
+struct ImpactText;
+
+#[test]
+fn trajectory_impacts_platform_below_test() {
+    let pad = LandingPad {
+        center: Vec2::new(0.0, 0.0),
+        half_width: 60.0,
+        factor: 10,
+    };
+    let terrain = vec![Vec2::new(-500.0, -10.0), Vec2::new(500.0, -10.0)];
+    let trajectory = predict_trajectory(Vec2::new(0.0, 100.0), Vec2::ZERO, 58.0, &terrain, &[pad]);
+    let impact = trajectory.impact.unwrap();
+    assert_eq!(impact.platform_factor, Some(10));
+    assert_eq!(impact.point.y, PLATFORM_HALF_HEIGHT);
+    assert!(impact.vertical_velocity < 0.0);
+    // far from the pad the terrain is hit instead
+    let trajectory =
+        predict_trajectory(Vec2::new(300.0, 100.0), Vec2::ZERO, 58.0, &terrain, &[pad]);
+    assert_eq!(trajectory.impact.unwrap().platform_factor, None);
+}
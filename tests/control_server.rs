@@ -0,0 +1,101 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::thread::sleep;
+use std::time::Duration;
+
+use rusty_lander::control_server::{
+    ClientCommand, ControlEndpoint, ControlServer, LanderState, ServerMessage,
+};
+use rusty_lander::spaceship::PlayerAction;
+use rusty_lander::state::GameState;
+
+fn poll_until(server: &mut ControlServer, count: usize) -> Vec<ClientCommand> {
+    let mut commands = vec![];
+    for _ in 0..200 {
+        commands.extend(server.poll());
+        if commands.len() >= count {
+            break;
+        }
+        sleep(Duration::from_millis(5));
+    }
+    commands
+}
+
+#[test]
+fn local_client_drives_the_control_server_test() {
+    let endpoint: ControlEndpoint = "tcp:127.0.0.1:0".parse().unwrap();
+    let mut server = ControlServer::bind(&endpoint).unwrap();
+    let address = server.local_addr().unwrap();
+    let mut client = TcpStream::connect(address).unwrap();
+    let mut client_reader = BufReader::new(client.try_clone().unwrap());
+    client
+        .write_all(
+            concat!(
+                "{\"command\":\"seed\",\"seed\":7}\n",
+                "{\"command\":\"actions\",\"actions\":[\"MainThrusterBig\",\"LeftThruster\"]}\n",
+                "{\"command\":\"pause\"}\n",
+                "{\"command\":\"reset\"}\n",
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+    let commands = poll_until(&mut server, 4);
+    assert_eq!(
+        commands,
+        vec![
+            ClientCommand::Seed { seed: 7 },
+            ClientCommand::Actions {
+                actions: vec![PlayerAction::MainThrusterBig, PlayerAction::LeftThruster]
+            },
+            ClientCommand::Pause,
+            ClientCommand::Reset { seed: None },
+        ]
+    );
+    assert_eq!(server.client_count(), 1);
+    // malformed lines are reported back to the sender only
+    client.write_all(b"{\"command\":\"fly\"}\n").unwrap();
+    assert!(poll_until(&mut server, 1).is_empty());
+    let mut line = String::new();
+    client_reader.read_line(&mut line).unwrap();
+    let message: ServerMessage = serde_json::from_str(&line).unwrap();
+    assert!(matches!(message, ServerMessage::Error { .. }));
+    // lander state is streamed as one line per message
+    let state = ServerMessage::State(LanderState {
+        tick: 1,
+        state: GameState::Landing,
+        position: [-300.0, 300.0].into(),
+        velocity: [120.0, 0.0].into(),
+        fuel: 1000.0,
+        gravity_scale: 1.0,
+        score: 0,
+        ready_to_land: false,
+    });
+    server.broadcast(&state);
+    line.clear();
+    client_reader.read_line(&mut line).unwrap();
+    assert_eq!(serde_json::from_str::<ServerMessage>(&line).unwrap(), state);
+    // disconnected clients are forgotten
+    drop(client_reader);
+    drop(client);
+    poll_until(&mut server, 1);
+    assert_eq!(server.client_count(), 0);
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_socket_endpoint_test() {
+    use std::os::unix::net::UnixStream;
+
+    let path = std::env::temp_dir().join(format!("rusty-lander-{}.sock", std::process::id()));
+    let endpoint: ControlEndpoint = format!("unix:{}", path.display()).parse().unwrap();
+    let mut server = ControlServer::bind(&endpoint).unwrap();
+    let mut client = UnixStream::connect(&path).unwrap();
+    client
+        .write_all(b"{\"command\":\"reset\",\"seed\":3}\n")
+        .unwrap();
+    assert_eq!(
+        poll_until(&mut server, 1),
+        vec![ClientCommand::Reset { seed: Some(3) }]
+    );
+    std::fs::remove_file(path).unwrap();
+}